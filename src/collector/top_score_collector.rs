@@ -98,6 +98,37 @@ impl CustomScorer<u64> for ScorerByField {
     }
 }
 
+struct ScoreTweakerByFastFieldReader {
+    ff_reader: FastFieldReader<u64>,
+}
+
+impl ScoreSegmentTweaker<(Score, u64)> for ScoreTweakerByFastFieldReader {
+    fn score(&mut self, doc: DocId, score: Score) -> (Score, u64) {
+        (score, self.ff_reader.get_u64(u64::from(doc)))
+    }
+}
+
+struct ScoreTweakerByField {
+    field: Field,
+}
+
+impl ScoreTweaker<(Score, u64)> for ScoreTweakerByField {
+    type Child = ScoreTweakerByFastFieldReader;
+
+    fn segment_tweaker(&self, segment_reader: &SegmentReader) -> crate::Result<Self::Child> {
+        let ff_reader = segment_reader
+            .fast_fields()
+            .u64(self.field)
+            .ok_or_else(|| {
+                crate::TantivyError::SchemaError(format!(
+                    "Field requested ({:?}) is not a i64/u64 fast field.",
+                    self.field
+                ))
+            })?;
+        Ok(ScoreTweakerByFastFieldReader { ff_reader })
+    }
+}
+
 impl TopDocs {
     /// Creates a top score collector, with a number of documents equal to "limit".
     ///
@@ -222,6 +253,56 @@ impl TopDocs {
         self.custom_score(ScorerByField { field })
     }
 
+    /// Set top-K to rank documents by score, falling back to a given fast field
+    /// for documents having the same score.
+    ///
+    /// This is typically useful when the query does not discriminate documents
+    /// by score, as in a disjunction of filters all scoring `0`. In that case,
+    /// the ordering is entirely determined by the fast field.
+    ///
+    /// Documents having the same score and the same fast field value are
+    /// sorted by their `DocAddress`.
+    ///
+    /// ```rust
+    /// use tantivy::collector::TopDocs;
+    /// use tantivy::query::{AllQuery, BoostQuery};
+    /// use tantivy::schema::{Schema, FAST, TEXT};
+    /// use tantivy::{doc, DocAddress, Index};
+    /// # fn main() -> tantivy::Result<()> {
+    /// let mut schema_builder = Schema::builder();
+    /// let title = schema_builder.add_text_field("title", TEXT);
+    /// let rating = schema_builder.add_u64_field("rating", FAST);
+    /// let schema = schema_builder.build();
+    /// let index = Index::create_in_ram(schema);
+    /// let mut index_writer = index.writer_with_num_threads(1, 3_000_000)?;
+    /// index_writer.add_document(doc!(title => "The Name of the Wind", rating => 92u64));
+    /// index_writer.add_document(doc!(title => "The Diary of Muadib", rating => 97u64));
+    /// index_writer.commit()?;
+    /// let searcher = index.reader()?.searcher();
+    /// // All documents are scored `0`.
+    /// let query = BoostQuery::new(Box::new(AllQuery), 0.0);
+    /// let top_docs = searcher.search(
+    ///     &query,
+    ///     &TopDocs::with_limit(2).order_by_score_then_u64_field(rating),
+    /// )?;
+    /// assert_eq!(
+    ///     top_docs,
+    ///     vec![((0.0, 97u64), DocAddress(0, 1)), ((0.0, 92u64), DocAddress(0, 0))]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// May panic if the field requested is not a fast field.
+    pub fn order_by_score_then_u64_field(
+        self,
+        field: Field,
+    ) -> impl Collector<Fruit = Vec<((Score, u64), DocAddress)>> {
+        self.tweak_score(ScoreTweakerByField { field })
+    }
+
     /// Ranks the documents using a custom score.
     ///
     /// This method offers a convenient way to tweak or replace
@@ -547,7 +628,7 @@ impl SegmentCollector for TopScoreSegmentCollector {
 mod tests {
     use super::TopDocs;
     use crate::collector::Collector;
-    use crate::query::{AllQuery, Query, QueryParser};
+    use crate::query::{AllQuery, BoostQuery, Query, QueryParser};
     use crate::schema::{Field, Schema, FAST, STORED, TEXT};
     use crate::Index;
     use crate::IndexWriter;
@@ -762,6 +843,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_top_score_then_field_collector_with_zero_scores() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field(TITLE, TEXT);
+        let size = schema_builder.add_u64_field(SIZE, FAST);
+        let schema = schema_builder.build();
+        let (index, query) = index("beer", title, schema, |index_writer| {
+            index_writer.add_document(doc!(
+                title => "bottle of beer",
+                size => 12u64,
+            ));
+            index_writer.add_document(doc!(
+                title => "growler of beer",
+                size => 64u64,
+            ));
+            index_writer.add_document(doc!(
+                title => "pint of wine",
+                size => 32u64,
+            ));
+            index_writer.add_document(doc!(
+                title => "pint of beer",
+                size => 16u64,
+            ));
+        });
+        let searcher = index.reader().unwrap().searcher();
+        let zero_score_query = BoostQuery::new(query, 0.0);
+        let top_collector = TopDocs::with_limit(2).order_by_score_then_u64_field(size);
+        let top_docs: Vec<((Score, u64), DocAddress)> =
+            searcher.search(&zero_score_query, &top_collector).unwrap();
+        assert_eq!(
+            top_docs,
+            vec![((0.0, 64), DocAddress(0, 1)), ((0.0, 16), DocAddress(0, 3))]
+        );
+        // Without the fallback, ties are broken by ascending doc address.
+        let top_docs = searcher
+            .search(&zero_score_query, &TopDocs::with_limit(2))
+            .unwrap();
+        assert_eq!(
+            top_docs,
+            vec![(0.0, DocAddress(0, 0)), (0.0, DocAddress(0, 1))]
+        );
+    }
+
     #[test]
     fn test_tweak_score_top_collector_with_offset() {
         let index = make_index();
@@ -1,4 +1,5 @@
 use crate::fastfield::DeleteBitSet;
+use crate::query::{count_matches, TwoPhase};
 use crate::DocId;
 use std::borrow::Borrow;
 use std::borrow::BorrowMut;
@@ -84,7 +85,13 @@ pub trait DocSet {
 
     /// Returns the number documents matching.
     /// Calling this method consumes the `DocSet`.
+    ///
+    /// Two-phase `DocSet`s are counted by iterating through their approximation,
+    /// and confirming the documents with `.matches()`.
     fn count(&mut self, delete_bitset: &DeleteBitSet) -> u32 {
+        if let Some(two_phase) = self.two_phase() {
+            return count_matches(two_phase, Some(delete_bitset));
+        }
         let mut count = 0u32;
         let mut doc = self.doc();
         while doc != TERMINATED {
@@ -102,6 +109,9 @@ pub trait DocSet {
     /// Of course, the result is an upper bound of the result
     /// given by `count()`.
     fn count_including_deleted(&mut self) -> u32 {
+        if let Some(two_phase) = self.two_phase() {
+            return count_matches(two_phase, None);
+        }
         let mut count = 0u32;
         let mut doc = self.doc();
         while doc != TERMINATED {
@@ -110,6 +120,14 @@ pub trait DocSet {
         }
         count
     }
+
+    /// Returns the two-phase view of this `DocSet`, if it supports
+    /// two-phase iteration.
+    ///
+    /// See [`TwoPhase`](./query/trait.TwoPhase.html).
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        None
+    }
 }
 
 impl<'a> DocSet for &'a mut dyn DocSet {
@@ -128,6 +146,10 @@ impl<'a> DocSet for &'a mut dyn DocSet {
     fn size_hint(&self) -> u32 {
         (**self).size_hint()
     }
+
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        (**self).two_phase()
+    }
 }
 
 impl<TDocSet: DocSet + ?Sized> DocSet for Box<TDocSet> {
@@ -160,4 +182,9 @@ impl<TDocSet: DocSet + ?Sized> DocSet for Box<TDocSet> {
         let unboxed: &mut TDocSet = self.borrow_mut();
         unboxed.count_including_deleted()
     }
+
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        let unboxed: &mut TDocSet = self.borrow_mut();
+        unboxed.two_phase()
+    }
 }
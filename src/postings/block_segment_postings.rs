@@ -189,6 +189,42 @@ impl BlockSegmentPostings {
         self.load_block();
     }
 
+    /// Position the skip reader on the block that may contain `target_doc`,
+    /// without decoding it.
+    ///
+    /// The block metadata (`.last_doc_in_block()`, `.block_max_term_freq()`)
+    /// then describes this block, while the docs and freqs of the
+    /// previously loaded block are left untouched.
+    /// The next call to move this `BlockSegmentPostings` has to be a `.seek(...)`
+    /// to a target greater or equal to `target_doc`.
+    pub(crate) fn shallow_seek(&mut self, target_doc: DocId) {
+        self.skip_reader.seek(target_doc);
+    }
+
+    /// Returns the last doc of the block the skip reader is positioned on.
+    ///
+    /// The last block is not described by the skip data, and `TERMINATED`
+    /// is returned instead.
+    pub(crate) fn last_doc_in_block(&self) -> DocId {
+        self.skip_reader.last_doc_in_block()
+    }
+
+    /// Returns an upper bound of the term frequencies within the block the
+    /// skip reader is positioned on, or `None` if the skip data does not
+    /// give any.
+    ///
+    /// The bound is derived from the number of bits used to bitpack
+    /// the term frequencies.
+    pub(crate) fn block_max_term_freq(&self) -> Option<u32> {
+        match self.skip_reader.block_info() {
+            BlockInfo::BitPacked { tf_num_bits, .. } => {
+                let max_term_freq = (1u64 << tf_num_bits) - 1;
+                Some(max_term_freq.max(1) as u32)
+            }
+            BlockInfo::VInt(_) => None,
+        }
+    }
+
     fn load_block(&mut self) {
         let offset = self.skip_reader.byte_offset();
         if self.loaded_offset == offset {
@@ -234,11 +270,12 @@ impl BlockSegmentPostings {
     ///
     /// Returns false iff there was no remaining blocks.
     pub fn advance(&mut self) -> bool {
-        if !self.skip_reader.advance() {
-            return false;
-        }
+        let has_remaining_block = self.skip_reader.advance();
+        // Even past the last block, the (empty) block is loaded,
+        // so that the docs are all `TERMINATED`.
+        // This matters when the number of docs is a multiple of the block size.
         self.load_block();
-        true
+        has_remaining_block
     }
 
     /// Returns an empty segment postings object
@@ -349,6 +386,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_advance_past_last_full_block() {
+        let doc_ids: Vec<DocId> = (0..2 * COMPRESSION_BLOCK_SIZE as u32).collect();
+        let block_segments = build_block_postings(&doc_ids);
+        let mut docset = SegmentPostings::from_block_postings(block_segments, None);
+        for &doc in &doc_ids {
+            assert_eq!(docset.doc(), doc);
+            docset.advance();
+        }
+        assert_eq!(docset.doc(), TERMINATED);
+        assert_eq!(docset.advance(), TERMINATED);
+    }
+
     fn build_block_postings(docs: &[DocId]) -> BlockSegmentPostings {
         let mut schema_builder = Schema::builder();
         let int_field = schema_builder.add_u64_field("id", INDEXED);
@@ -1,6 +1,6 @@
 use crate::common::HasLen;
 
-use crate::docset::DocSet;
+use crate::docset::{DocSet, TERMINATED};
 use crate::positions::PositionReader;
 
 use crate::postings::compression::COMPRESSION_BLOCK_SIZE;
@@ -83,6 +83,46 @@ impl SegmentPostings {
             block_searcher: BlockSearcher::default(),
         }
     }
+
+    /// Positions the skip data on the block that may contain `target`,
+    /// without decoding it.
+    ///
+    /// See `BlockSegmentPostings::shallow_seek`. Only `.seek(...)` to a target
+    /// greater or equal to `target` may be called afterwards.
+    pub(crate) fn shallow_seek(&mut self, target: DocId) {
+        self.block_cursor.shallow_seek(target);
+    }
+
+    /// Returns the last doc of the block the skip data is positioned on.
+    pub(crate) fn last_doc_in_block(&self) -> DocId {
+        self.block_cursor.last_doc_in_block()
+    }
+
+    /// Returns an upper bound of the term frequencies within the block
+    /// the skip data is positioned on, if it is known.
+    pub(crate) fn block_max_term_freq(&self) -> Option<u32> {
+        self.block_cursor.block_max_term_freq()
+    }
+
+    /// Calls `callback` with each of the remaining documents and
+    /// its term frequency, starting with the current document.
+    ///
+    /// The documents are read directly from the decoded blocks.
+    /// After this call, the postings are positioned on `TERMINATED`.
+    pub(crate) fn for_each_term_freq<F: FnMut(DocId, u32)>(&mut self, mut callback: F) {
+        loop {
+            for cur in self.cur..COMPRESSION_BLOCK_SIZE {
+                let doc = self.block_cursor.doc(cur);
+                if doc == TERMINATED {
+                    self.cur = cur;
+                    return;
+                }
+                callback(doc, self.block_cursor.freq(cur));
+            }
+            self.cur = 0;
+            self.block_cursor.advance();
+        }
+    }
 }
 
 impl DocSet for SegmentPostings {
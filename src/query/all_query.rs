@@ -1,9 +1,10 @@
 use crate::core::Searcher;
 use crate::core::SegmentReader;
 use crate::docset::{DocSet, TERMINATED};
+use crate::fastfield::DeleteBitSet;
 use crate::query::boost_query::BoostScorer;
 use crate::query::explanation::does_not_match;
-use crate::query::{Explanation, Query, Scorer, Weight};
+use crate::query::{ConstScorer, Explanation, Query, Scorer, Weight};
 use crate::DocId;
 use crate::Score;
 
@@ -24,10 +25,7 @@ pub struct AllWeight;
 
 impl Weight for AllWeight {
     fn scorer(&self, reader: &SegmentReader, boost: f32) -> crate::Result<Box<dyn Scorer>> {
-        let all_scorer = AllScorer {
-            doc: 0u32,
-            max_doc: reader.max_doc(),
-        };
+        let all_scorer = AllScorer::new(reader.max_doc());
         Ok(Box::new(BoostScorer::new(all_scorer, boost)))
     }
 
@@ -45,6 +43,14 @@ pub struct AllScorer {
     max_doc: DocId,
 }
 
+impl AllScorer {
+    /// Creates a new `AllScorer`, going through the documents `0..max_doc`.
+    pub(crate) fn new(max_doc: DocId) -> AllScorer {
+        let doc = if max_doc == 0 { TERMINATED } else { 0u32 };
+        AllScorer { doc, max_doc }
+    }
+}
+
 impl DocSet for AllScorer {
     fn advance(&mut self) -> DocId {
         if self.doc + 1 >= self.max_doc {
@@ -55,6 +61,15 @@ impl DocSet for AllScorer {
         self.doc
     }
 
+    fn seek(&mut self, target: DocId) -> DocId {
+        if target >= self.max_doc {
+            self.doc = TERMINATED;
+            return TERMINATED;
+        }
+        self.doc = self.doc.max(target);
+        self.doc
+    }
+
     fn doc(&self) -> DocId {
         self.doc
     }
@@ -70,13 +85,114 @@ impl Scorer for AllScorer {
     }
 }
 
+/// Weight matching all of the documents of a segment that are not deleted.
+///
+/// All of the documents get the same constant score, `1f32` by default.
+///
+/// Contrary to `AllWeight`, which goes through all of the documents and
+/// leaves it to the collectors to skip the deleted ones, its scorer skips
+/// the deleted documents itself.
+pub struct MatchAllWeight {
+    score: Score,
+}
+
+impl MatchAllWeight {
+    /// Creates a new `MatchAllWeight`, giving the score `score` to all documents.
+    pub fn new(score: Score) -> MatchAllWeight {
+        MatchAllWeight { score }
+    }
+}
+
+impl Default for MatchAllWeight {
+    fn default() -> Self {
+        MatchAllWeight::new(1f32)
+    }
+}
+
+impl Weight for MatchAllWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: f32) -> crate::Result<Box<dyn Scorer>> {
+        let alive_docs = AliveDocSet::new(reader.max_doc(), reader.delete_bitset().cloned());
+        Ok(Box::new(ConstScorer::new(alive_docs, self.score * boost)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        if doc >= reader.max_doc() || reader.is_deleted(doc) {
+            return Err(does_not_match(doc));
+        }
+        Ok(Explanation::new("MatchAllWeight", self.score))
+    }
+
+    fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
+        Ok(reader.num_docs())
+    }
+}
+
+/// `DocSet` of the documents of a segment that are not deleted.
+///
+/// It goes through the documents of an `AllScorer`, skipping the deleted ones.
+struct AliveDocSet {
+    all_docs: AllScorer,
+    delete_bitset_opt: Option<DeleteBitSet>,
+}
+
+impl AliveDocSet {
+    fn new(max_doc: DocId, delete_bitset_opt: Option<DeleteBitSet>) -> AliveDocSet {
+        let mut alive_docs = AliveDocSet {
+            all_docs: AllScorer::new(max_doc),
+            delete_bitset_opt,
+        };
+        alive_docs.skip_deleted();
+        alive_docs
+    }
+
+    /// Advances the docset until it is positioned on a document
+    /// that is not deleted.
+    fn skip_deleted(&mut self) -> DocId {
+        let mut doc = self.all_docs.doc();
+        if let Some(delete_bitset) = self.delete_bitset_opt.as_ref() {
+            while doc != TERMINATED && delete_bitset.is_deleted(doc) {
+                doc = self.all_docs.advance();
+            }
+        }
+        doc
+    }
+}
+
+impl DocSet for AliveDocSet {
+    fn advance(&mut self) -> DocId {
+        if self.all_docs.advance() == TERMINATED {
+            return TERMINATED;
+        }
+        self.skip_deleted()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if self.doc() >= target {
+            return self.doc();
+        }
+        self.all_docs.seek(target);
+        self.skip_deleted()
+    }
+
+    fn doc(&self) -> DocId {
+        self.all_docs.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.all_docs.size_hint()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::AllQuery;
+    use super::{AliveDocSet, AllQuery, MatchAllWeight};
+    use crate::directory::ReadOnlySource;
+    use crate::docset::DocSet;
     use crate::docset::TERMINATED;
-    use crate::query::Query;
-    use crate::schema::{Schema, TEXT};
-    use crate::Index;
+    use crate::fastfield::DeleteBitSet;
+    use crate::query::{Query, Weight};
+    use crate::schema::{Schema, INDEXED, TEXT};
+    use crate::{Index, Term};
 
     fn create_test_index() -> Index {
         let mut schema_builder = Schema::builder();
@@ -132,4 +248,64 @@ mod tests {
             assert_eq!(scorer.score(), 1.5f32);
         }
     }
+
+    #[test]
+    fn test_match_all_weight() {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_u64_field("id", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 10_000_000).unwrap();
+            for i in 0u64..6u64 {
+                index_writer.add_document(doc!(id=>i));
+            }
+            index_writer.delete_term(Term::from_field_u64(id, 0u64));
+            index_writer.delete_term(Term::from_field_u64(id, 3u64));
+            index_writer.delete_term(Term::from_field_u64(id, 5u64));
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let reader = searcher.segment_reader(0);
+        let weight = MatchAllWeight::new(2f32);
+        {
+            let mut scorer = weight.scorer(reader, 1.5f32).unwrap();
+            assert_eq!(scorer.doc(), 1u32);
+            assert_eq!(scorer.score(), 3f32);
+            assert_eq!(scorer.advance(), 2u32);
+            assert_eq!(scorer.advance(), 4u32);
+            assert_eq!(scorer.advance(), TERMINATED);
+            assert_eq!(scorer.advance(), TERMINATED);
+        }
+        {
+            let mut scorer = weight.scorer(reader, 1.0f32).unwrap();
+            assert_eq!(scorer.seek(3u32), 4u32);
+            assert_eq!(scorer.seek(6u32), TERMINATED);
+        }
+        {
+            let mut scorer = weight.scorer(reader, 1.0f32).unwrap();
+            assert_eq!(scorer.seek(100u32), TERMINATED);
+        }
+        assert_eq!(weight.count(reader).unwrap(), 3);
+        assert_eq!(weight.explain(reader, 2).unwrap().value(), 2f32);
+        assert!(weight.explain(reader, 3).is_err());
+        assert!(weight.explain(reader, 6).is_err());
+        assert_eq!(
+            MatchAllWeight::default()
+                .explain(reader, 1)
+                .unwrap()
+                .value(),
+            1f32
+        );
+    }
+
+    #[test]
+    fn test_alive_docset_all_deleted() {
+        // Segments whose documents are all deleted are dropped on commit:
+        // the delete bitset is built by hand.
+        let delete_bitset = DeleteBitSet::open(ReadOnlySource::from(vec![255u8, 3u8]));
+        let mut alive_docs = AliveDocSet::new(10u32, Some(delete_bitset));
+        assert_eq!(alive_docs.doc(), TERMINATED);
+        assert_eq!(alive_docs.advance(), TERMINATED);
+        assert_eq!(alive_docs.seek(5u32), TERMINATED);
+    }
 }
@@ -96,6 +96,18 @@ impl BM25Weight {
         }
     }
 
+    /// Returns an upper bound of the scores computed by this `BM25Weight`.
+    pub fn max_score(&self) -> Score {
+        self.weight
+    }
+
+    /// Returns an upper bound of the scores of the documents with
+    /// a term frequency lower or equal to `max_term_freq`.
+    pub fn max_score_for_term_freq(&self, max_term_freq: u32) -> Score {
+        // The shortest fieldnorm gives the highest score.
+        self.score(0u8, max_term_freq)
+    }
+
     #[inline(always)]
     pub fn score(&self, fieldnorm_id: u8, term_freq: u32) -> Score {
         let norm = self.cache[fieldnorm_id as usize];
@@ -0,0 +1,245 @@
+use crate::docset::{DocSet, TERMINATED};
+use crate::query::term_query::TermScorer;
+use crate::query::Scorer;
+use crate::{DocId, Score};
+
+/// Top-k scoring of a union of `TermScorer`s, using the Block-Max WAND
+/// algorithm.
+///
+/// The union is only driven through `.for_each_pruning(...)`: the
+/// threshold supplied by the caller, typically the lowest score of a
+/// top-k heap, makes it possible to skip the documents that cannot
+/// exceed it.
+///
+/// Two kinds of upper bounds are used:
+/// - the max score of each term, to find the first document (the pivot)
+///   that could possibly exceed the threshold.
+/// - the max score of the blocks of the postings containing the pivot,
+///   derived from the skip data, to skip entire blocks without decoding them.
+///
+/// The scores are the sum of the scores of the terms, as computed by
+/// a `Union` with a `SumWithCoordsCombiner`.
+pub(crate) struct BlockMaxWand {
+    scorers: Vec<TermScorer>,
+}
+
+impl BlockMaxWand {
+    /// Creates a `BlockMaxWand` over the given `TermScorer`s.
+    pub fn new(scorers: Vec<TermScorer>) -> BlockMaxWand {
+        let scorers: Vec<TermScorer> = scorers
+            .into_iter()
+            .filter(|scorer| scorer.doc() != TERMINATED)
+            .collect();
+        BlockMaxWand { scorers }
+    }
+
+    /// Calls `callback` with the `(doc, score)` of the documents whose
+    /// score exceeds the threshold.
+    ///
+    /// The threshold is initially `threshold`, and is updated
+    /// with the value returned by `callback`.
+    /// Documents are visited in increasing doc id order.
+    pub fn for_each_pruning(
+        mut self,
+        mut threshold: Score,
+        callback: &mut dyn FnMut(DocId, Score) -> Score,
+    ) {
+        self.sort_by_doc();
+        while let Some((pivot_len, pivot_doc)) = self.find_pivot(threshold) {
+            let block_max_score: Score = self.scorers[..pivot_len]
+                .iter_mut()
+                .map(|scorer| {
+                    scorer.shallow_seek(pivot_doc);
+                    scorer.block_max_score()
+                })
+                .sum();
+            if block_max_score <= threshold {
+                // None of the docs until the end of the shortest of these blocks
+                // can exceed the threshold.
+                let target = self.block_skip_target(pivot_len);
+                self.seek_lagging_scorers(pivot_len, target);
+            } else if self.scorers[0].doc() == pivot_doc {
+                // The scorers are sorted by doc: all of the first `pivot_len`
+                // scorers are on the pivot.
+                let score: Score = self.scorers[..pivot_len]
+                    .iter_mut()
+                    .map(|scorer| scorer.score())
+                    .sum();
+                if score > threshold {
+                    threshold = callback(pivot_doc, score);
+                }
+                for scorer in &mut self.scorers[..pivot_len] {
+                    scorer.advance();
+                }
+            } else {
+                self.seek_lagging_scorers(pivot_len, pivot_doc);
+            }
+            self.scorers.retain(|scorer| scorer.doc() != TERMINATED);
+            self.sort_by_doc();
+        }
+    }
+
+    fn sort_by_doc(&mut self) {
+        self.scorers.sort_by_key(|scorer| scorer.doc());
+    }
+
+    /// Finds the first doc whose score may exceed the threshold,
+    /// assuming all of its terms reach their max score.
+    ///
+    /// Returns the number of scorers positioned before or on this doc,
+    /// and the doc itself.
+    fn find_pivot(&self, threshold: Score) -> Option<(usize, DocId)> {
+        let mut max_score = 0f32;
+        for (ord, scorer) in self.scorers.iter().enumerate() {
+            max_score += scorer.max_score();
+            if max_score > threshold {
+                let pivot_doc = scorer.doc();
+                let pivot_len = ord
+                    + 1
+                    + self.scorers[ord + 1..]
+                        .iter()
+                        .take_while(|scorer| scorer.doc() == pivot_doc)
+                        .count();
+                return Some((pivot_len, pivot_doc));
+            }
+        }
+        None
+    }
+
+    /// Returns the first doc that is not covered by the blocks the
+    /// first `pivot_len` scorers are shallowly positioned on, nor reached
+    /// by the other scorers.
+    fn block_skip_target(&self, pivot_len: usize) -> DocId {
+        let last_doc_in_blocks = self.scorers[..pivot_len]
+            .iter()
+            .map(|scorer| scorer.last_doc_in_block())
+            .min()
+            .unwrap_or(TERMINATED);
+        let block_end = if last_doc_in_blocks == TERMINATED {
+            TERMINATED
+        } else {
+            last_doc_in_blocks + 1
+        };
+        self.scorers
+            .get(pivot_len)
+            .map(|scorer| scorer.doc().min(block_end))
+            .unwrap_or(block_end)
+    }
+
+    fn seek_lagging_scorers(&mut self, pivot_len: usize, target: DocId) {
+        for scorer in &mut self.scorers[..pivot_len] {
+            if scorer.doc() < target {
+                scorer.seek(target);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collector::tests::TEST_COLLECTOR_WITH_SCORE;
+    use crate::collector::TopDocs;
+    use crate::query::{BooleanQuery, Occur, Query, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, TEXT};
+    use crate::tests::assert_nearly_equals;
+    use crate::{DocAddress, Index, Score, Term};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const WORDS: [&str; 6] = ["a", "b", "c", "d", "e", "f"];
+
+    fn create_random_index(num_docs: usize, seed_val: u8) -> Index {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut rng = StdRng::from_seed([seed_val; 32]);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            for _ in 0..num_docs {
+                let num_tokens = rng.gen_range(1, 20);
+                let tokens: Vec<&str> = (0..num_tokens)
+                    .map(|_| {
+                        // Skewed distribution, so that the terms have
+                        // different doc freqs and term freqs.
+                        let ord = rng.gen_range(0, WORDS.len() * WORDS.len());
+                        WORDS[(ord as f64).sqrt() as usize]
+                    })
+                    .collect();
+                index_writer.add_document(doc!(text_field=>tokens.join(" ")));
+            }
+            assert!(index_writer.commit().is_ok());
+        }
+        index
+    }
+
+    fn test_block_wand_aux(index: &Index, words: &[&str], k: usize) {
+        let text_field = index.schema().get_field("text").unwrap();
+        let term_queries: Vec<(Occur, Box<dyn Query>)> = words
+            .iter()
+            .map(|word| {
+                let term = Term::from_field_text(text_field, word);
+                let query: Box<dyn Query> =
+                    Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs));
+                (Occur::Should, query)
+            })
+            .collect();
+        let query = BooleanQuery::from(term_queries);
+        let searcher = index.reader().unwrap().searcher();
+
+        let top_k: Vec<(Score, DocAddress)> =
+            searcher.search(&query, &TopDocs::with_limit(k)).unwrap();
+
+        let exhaustive = searcher.search(&query, &TEST_COLLECTOR_WITH_SCORE).unwrap();
+        let mut brute_force: Vec<(Score, DocAddress)> = exhaustive
+            .scores()
+            .iter()
+            .cloned()
+            .zip(exhaustive.docs().iter().cloned())
+            .collect();
+        brute_force.sort_by(|left, right| right.0.partial_cmp(&left.0).unwrap());
+        brute_force.truncate(k);
+
+        assert_eq!(top_k.len(), brute_force.len());
+        for (&(score, _), &(expected_score, _)) in top_k.iter().zip(brute_force.iter()) {
+            assert_nearly_equals(expected_score, score);
+        }
+        // Documents tied with the lowest score of the top k may legitimately
+        // differ. All of the other ones have to be returned.
+        if let Some(&(min_score, _)) = brute_force.last() {
+            let above_min_score = |hits: &[(Score, DocAddress)]| {
+                let mut docs: Vec<DocAddress> = hits
+                    .iter()
+                    .filter(|&&(score, _)| score > min_score * 1.0001f32)
+                    .map(|&(_, doc)| doc)
+                    .collect();
+                docs.sort();
+                docs
+            };
+            assert_eq!(
+                above_min_score(&top_k[..]),
+                above_min_score(&brute_force[..])
+            );
+        }
+    }
+
+    #[test]
+    fn test_block_wand_against_brute_force() {
+        for seed_val in 0u8..3u8 {
+            let index = create_random_index(3_000, seed_val);
+            for &k in &[1, 10, 100] {
+                test_block_wand_aux(&index, &["a", "b"], k);
+                test_block_wand_aux(&index, &["d", "e", "f"], k);
+                test_block_wand_aux(&index, &["a", "c", "f"], k);
+                test_block_wand_aux(&index, &["a", "b", "c", "d", "e", "f"], k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_block_wand_with_missing_term() {
+        let index = create_random_index(500, 7u8);
+        test_block_wand_aux(&index, &["a", "missing"], 10);
+        test_block_wand_aux(&index, &["missing", "f", "other"], 10);
+    }
+}
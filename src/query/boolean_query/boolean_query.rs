@@ -1,4 +1,5 @@
 use super::boolean_weight::BooleanWeight;
+use crate::query::CombinerKind;
 use crate::query::Occur;
 use crate::query::Query;
 use crate::query::TermQuery;
@@ -123,27 +124,47 @@ use std::collections::BTreeSet;
 ///    ]);
 ///    let count4 = searcher.search(&nested_query, &Count)?;
 ///    assert_eq!(count4, 1);
+///
+///    // At least two of "diary", "dairy" and "cow" must be present
+///    let two_of_three = BooleanQuery::new_multiterms_query(vec![
+///        Term::from_field_text(title, "diary"),
+///        Term::from_field_text(title, "dairy"),
+///        Term::from_field_text(title, "cow"),
+///    ])
+///    .with_minimum_should_match(2);
+///    let count5 = searcher.search(&two_of_three, &Count)?;
+///    assert_eq!(count5, 2);
 ///    Ok(())
 ///}
 /// ```
 #[derive(Debug)]
 pub struct BooleanQuery {
     subqueries: Vec<(Occur, Box<dyn Query>)>,
+    minimum_should_match: Option<usize>,
+    combiner: CombinerKind,
 }
 
 impl Clone for BooleanQuery {
     fn clone(&self) -> Self {
-        self.subqueries
-            .iter()
-            .map(|(occur, subquery)| (*occur, subquery.box_clone()))
-            .collect::<Vec<_>>()
-            .into()
+        BooleanQuery {
+            subqueries: self
+                .subqueries
+                .iter()
+                .map(|(occur, subquery)| (*occur, subquery.box_clone()))
+                .collect(),
+            minimum_should_match: self.minimum_should_match,
+            combiner: self.combiner,
+        }
     }
 }
 
 impl From<Vec<(Occur, Box<dyn Query>)>> for BooleanQuery {
     fn from(subqueries: Vec<(Occur, Box<dyn Query>)>) -> BooleanQuery {
-        BooleanQuery { subqueries }
+        BooleanQuery {
+            subqueries,
+            minimum_should_match: None,
+            combiner: CombinerKind::default(),
+        }
     }
 }
 
@@ -156,7 +177,12 @@ impl Query for BooleanQuery {
                 Ok((*occur, subquery.weight(searcher, scoring_enabled)?))
             })
             .collect::<crate::Result<_>>()?;
-        Ok(Box::new(BooleanWeight::new(sub_weights, scoring_enabled)))
+        Ok(Box::new(BooleanWeight::new(
+            sub_weights,
+            scoring_enabled,
+            self.minimum_should_match,
+            self.combiner,
+        )))
     }
 
     fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
@@ -181,6 +207,26 @@ impl BooleanQuery {
         BooleanQuery::from(occur_term_queries)
     }
 
+    /// Requires documents to match at least `minimum_should_match` of the
+    /// `Should` clauses.
+    ///
+    /// `Must` and `MustNot` clauses are not affected. If the threshold exceeds
+    /// the number of `Should` clauses, the query matches no documents.
+    /// A threshold of 1 is the same as no threshold: next to `Must` clauses,
+    /// the `Should` clauses remain optional.
+    pub fn with_minimum_should_match(mut self, minimum_should_match: usize) -> BooleanQuery {
+        self.minimum_should_match = Some(minimum_should_match);
+        self
+    }
+
+    /// Sets how the scores of the matching clauses are combined.
+    ///
+    /// By default, the scores are summed with `CombinerKind::SumWithCoords`.
+    pub fn with_combiner(mut self, combiner: CombinerKind) -> BooleanQuery {
+        self.combiner = combiner;
+        self
+    }
+
     /// Deconstructed view of the clauses making up this query.
     pub fn clauses(&self) -> &[(Occur, Box<dyn Query>)] {
         &self.subqueries[..]
@@ -1,46 +1,75 @@
 use crate::core::SegmentReader;
+use crate::query::boolean_query::block_wand::BlockMaxWand;
 use crate::query::explanation::does_not_match;
-use crate::query::score_combiner::{DoNothingCombiner, ScoreCombiner, SumWithCoordsCombiner};
+use crate::query::score_combiner::{
+    DoNothingCombiner, MaxScoreCombiner, ScoreCombiner, SumCombiner, SumWithCoordsCombiner,
+};
 use crate::query::term_query::TermScorer;
 use crate::query::weight::{for_each_pruning_scorer, for_each_scorer};
+use crate::query::CombinerKind;
 use crate::query::EmptyScorer;
 use crate::query::Exclude;
+use crate::query::MinShouldMatchScorer;
 use crate::query::Occur;
 use crate::query::RequiredOptionalScorer;
 use crate::query::Scorer;
+use crate::query::TwoPhaseUnion;
 use crate::query::Union;
 use crate::query::Weight;
 use crate::query::{intersect_scorers, Explanation};
 use crate::{DocId, Score};
 use std::collections::HashMap;
 
-enum SpecializedScorer<TScoreCombiner: ScoreCombiner> {
+pub(crate) enum SpecializedScorer<TScoreCombiner: ScoreCombiner> {
     TermUnion(Union<TermScorer, TScoreCombiner>),
     Other(Box<dyn Scorer>),
 }
 
-fn scorer_union<TScoreCombiner>(scorers: Vec<Box<dyn Scorer>>) -> SpecializedScorer<TScoreCombiner>
+pub(crate) fn scorer_union<TScoreCombiner, TScoreCombinerFn>(
+    scorers: Vec<Box<dyn Scorer>>,
+    score_combiner_fn: TScoreCombinerFn,
+) -> SpecializedScorer<TScoreCombiner>
 where
     TScoreCombiner: ScoreCombiner,
+    TScoreCombinerFn: Fn() -> TScoreCombiner,
 {
     assert!(!scorers.is_empty());
     if scorers.len() == 1 {
         return SpecializedScorer::Other(scorers.into_iter().next().unwrap()); //< we checked the size beforehands
     }
 
-    {
-        let is_all_term_queries = scorers.iter().all(|scorer| scorer.is::<TermScorer>());
-        if is_all_term_queries {
-            let scorers: Vec<TermScorer> = scorers
-                .into_iter()
-                .map(|scorer| *(scorer.downcast::<TermScorer>().map_err(|_| ()).unwrap()))
-                .collect();
-            return SpecializedScorer::TermUnion(Union::<TermScorer, TScoreCombiner>::from(
-                scorers,
-            ));
+    match into_term_scorers(scorers) {
+        Ok(term_scorers) => {
+            SpecializedScorer::TermUnion(Union::build(term_scorers, score_combiner_fn))
         }
+        Err(mut scorers) => {
+            let has_two_phase_scorer = scorers
+                .iter_mut()
+                .any(|scorer| scorer.two_phase().is_some());
+            if has_two_phase_scorer {
+                // Lets the costly scorers be confirmed lazily.
+                SpecializedScorer::Other(Box::new(TwoPhaseUnion::build(scorers, score_combiner_fn)))
+            } else {
+                SpecializedScorer::Other(Box::new(Union::build(scorers, score_combiner_fn)))
+            }
+        }
+    }
+}
+
+/// Downcasts the scorers to `TermScorer`s, if they all are `TermScorer`s.
+///
+/// Otherwise, the scorers are returned untouched.
+fn into_term_scorers(
+    scorers: Vec<Box<dyn Scorer>>,
+) -> Result<Vec<TermScorer>, Vec<Box<dyn Scorer>>> {
+    let is_all_term_queries = scorers.iter().all(|scorer| scorer.is::<TermScorer>());
+    if !is_all_term_queries {
+        return Err(scorers);
     }
-    SpecializedScorer::Other(Box::new(Union::<_, TScoreCombiner>::from(scorers)))
+    Ok(scorers
+        .into_iter()
+        .map(|scorer| *(scorer.downcast::<TermScorer>().map_err(|_| ()).unwrap()))
+        .collect())
 }
 
 impl<TScoreCombiner: ScoreCombiner> Into<Box<dyn Scorer>> for SpecializedScorer<TScoreCombiner> {
@@ -55,13 +84,62 @@ impl<TScoreCombiner: ScoreCombiner> Into<Box<dyn Scorer>> for SpecializedScorer<
 pub struct BooleanWeight {
     weights: Vec<(Occur, Box<dyn Weight>)>,
     scoring_enabled: bool,
+    minimum_should_match: Option<usize>,
+    combiner: CombinerKind,
 }
 
 impl BooleanWeight {
-    pub fn new(weights: Vec<(Occur, Box<dyn Weight>)>, scoring_enabled: bool) -> BooleanWeight {
+    /// Creates a new `BooleanWeight`.
+    ///
+    /// If `minimum_should_match` is set above 1, a document needs to match at
+    /// least that many `Should` clauses, in addition to all of the `Must` clauses.
+    /// A threshold of 1 behaves as the plain union of the `Should` clauses.
+    ///
+    /// The scores of the matching clauses are combined as selected by
+    /// `combiner`. It is ignored if scoring is disabled.
+    pub fn new(
+        weights: Vec<(Occur, Box<dyn Weight>)>,
+        scoring_enabled: bool,
+        minimum_should_match: Option<usize>,
+        combiner: CombinerKind,
+    ) -> BooleanWeight {
         BooleanWeight {
             weights,
             scoring_enabled,
+            minimum_should_match,
+            combiner,
+        }
+    }
+
+    fn num_should_clauses(&self) -> usize {
+        self.weights
+            .iter()
+            .filter(|&&(occur, _)| occur == Occur::Should)
+            .count()
+    }
+
+    /// Returns true if no document can match the minimum number of
+    /// `Should` clauses.
+    fn minimum_should_match_unreachable(&self) -> bool {
+        self.minimum_should_match
+            .map(|minimum_should_match| minimum_should_match > self.num_should_clauses())
+            .unwrap_or(false)
+    }
+
+    fn should_scorer<TScoreCombiner: ScoreCombiner>(
+        &self,
+        scorers: Vec<Box<dyn Scorer>>,
+    ) -> SpecializedScorer<TScoreCombiner> {
+        match self.minimum_should_match {
+            Some(minimum_should_match) if minimum_should_match > 1 => {
+                SpecializedScorer::Other(Box::new(MinShouldMatchScorer::<
+                    Box<dyn Scorer>,
+                    TScoreCombiner,
+                >::new(
+                    scorers, minimum_should_match
+                )))
+            }
+            _ => scorer_union(scorers, TScoreCombiner::default),
         }
     }
 
@@ -81,6 +159,21 @@ impl BooleanWeight {
         Ok(per_occur_scorers)
     }
 
+    /// Returns true if the `Should` clauses can be scored with `BlockMaxWand`.
+    ///
+    /// This requires the query to only have `Should` clauses, and to be
+    /// scored as their sum.
+    fn is_block_wand_candidate(&self) -> bool {
+        self.scoring_enabled
+            && (self.combiner == CombinerKind::SumWithCoords || self.combiner == CombinerKind::Sum)
+            && !self.weights.is_empty()
+            && self.minimum_should_match.unwrap_or(0) <= 1
+            && self
+                .weights
+                .iter()
+                .all(|&(occur, _)| occur == Occur::Should)
+    }
+
     fn complex_scorer<TScoreCombiner: ScoreCombiner>(
         &self,
         reader: &SegmentReader,
@@ -88,13 +181,17 @@ impl BooleanWeight {
     ) -> crate::Result<SpecializedScorer<TScoreCombiner>> {
         let mut per_occur_scorers = self.per_occur_scorers(reader, boost)?;
 
+        if self.minimum_should_match_unreachable() {
+            return Ok(SpecializedScorer::Other(Box::new(EmptyScorer)));
+        }
+
         let should_scorer_opt: Option<SpecializedScorer<TScoreCombiner>> = per_occur_scorers
             .remove(&Occur::Should)
-            .map(scorer_union::<TScoreCombiner>);
+            .map(|scorers| self.should_scorer(scorers));
 
         let exclude_scorer_opt: Option<Box<dyn Scorer>> = per_occur_scorers
             .remove(&Occur::MustNot)
-            .map(scorer_union::<TScoreCombiner>)
+            .map(|scorers| scorer_union(scorers, DoNothingCombiner::default))
             .map(Into::into);
 
         let must_scorer_opt: Option<Box<dyn Scorer>> = per_occur_scorers
@@ -104,7 +201,13 @@ impl BooleanWeight {
         let positive_scorer: SpecializedScorer<TScoreCombiner> =
             match (should_scorer_opt, must_scorer_opt) {
                 (Some(should_scorer), Some(must_scorer)) => {
-                    if self.scoring_enabled {
+                    if self.minimum_should_match.unwrap_or(0) > 1 {
+                        // The should clauses are not optional anymore.
+                        SpecializedScorer::Other(intersect_scorers(vec![
+                            must_scorer,
+                            should_scorer.into(),
+                        ]))
+                    } else if self.scoring_enabled {
                         SpecializedScorer::Other(Box::new(RequiredOptionalScorer::<
                             Box<dyn Scorer>,
                             Box<dyn Scorer>,
@@ -133,11 +236,64 @@ impl BooleanWeight {
             Ok(positive_scorer)
         }
     }
+
+    fn for_each_with_combiner<TScoreCombiner: ScoreCombiner>(
+        &self,
+        reader: &SegmentReader,
+        callback: &mut dyn FnMut(DocId, Score),
+    ) -> crate::Result<()> {
+        let scorer = self.complex_scorer::<TScoreCombiner>(reader, 1.0f32)?;
+        match scorer {
+            SpecializedScorer::TermUnion(mut union_scorer) => {
+                for_each_scorer(&mut union_scorer, callback);
+            }
+            SpecializedScorer::Other(mut scorer) => {
+                for_each_scorer(scorer.as_mut(), callback);
+            }
+        }
+        Ok(())
+    }
+
+    fn for_each_pruning_with_combiner<TScoreCombiner: ScoreCombiner>(
+        &self,
+        threshold: f32,
+        reader: &SegmentReader,
+        callback: &mut dyn FnMut(DocId, Score) -> Score,
+    ) -> crate::Result<()> {
+        if self.is_block_wand_candidate() {
+            let scorers: Vec<Box<dyn Scorer>> = self
+                .weights
+                .iter()
+                .map(|(_, weight)| weight.scorer(reader, 1.0f32))
+                .collect::<crate::Result<_>>()?;
+            match into_term_scorers(scorers) {
+                Ok(term_scorers) => {
+                    BlockMaxWand::new(term_scorers).for_each_pruning(threshold, callback);
+                }
+                Err(scorers) => {
+                    let mut union_scorer: Box<dyn Scorer> =
+                        scorer_union(scorers, TScoreCombiner::default).into();
+                    for_each_pruning_scorer(union_scorer.as_mut(), threshold, callback);
+                }
+            }
+            return Ok(());
+        }
+        let scorer = self.complex_scorer::<TScoreCombiner>(reader, 1.0f32)?;
+        match scorer {
+            SpecializedScorer::TermUnion(mut union_scorer) => {
+                for_each_pruning_scorer(&mut union_scorer, threshold, callback);
+            }
+            SpecializedScorer::Other(mut scorer) => {
+                for_each_pruning_scorer(scorer.as_mut(), threshold, callback);
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Weight for BooleanWeight {
     fn scorer(&self, reader: &SegmentReader, boost: f32) -> crate::Result<Box<dyn Scorer>> {
-        if self.weights.is_empty() {
+        if self.weights.is_empty() || self.minimum_should_match_unreachable() {
             Ok(Box::new(EmptyScorer))
         } else if self.weights.len() == 1 {
             let &(occur, ref weight) = &self.weights[0];
@@ -147,8 +303,20 @@ impl Weight for BooleanWeight {
                 weight.scorer(reader, boost)
             }
         } else if self.scoring_enabled {
-            self.complex_scorer::<SumWithCoordsCombiner>(reader, boost)
-                .map(Into::into)
+            match self.combiner {
+                CombinerKind::SumWithCoords => self
+                    .complex_scorer::<SumWithCoordsCombiner>(reader, boost)
+                    .map(Into::into),
+                CombinerKind::Sum => self
+                    .complex_scorer::<SumCombiner>(reader, boost)
+                    .map(Into::into),
+                CombinerKind::Max => self
+                    .complex_scorer::<MaxScoreCombiner>(reader, boost)
+                    .map(Into::into),
+                CombinerKind::None => self
+                    .complex_scorer::<DoNothingCombiner>(reader, boost)
+                    .map(Into::into),
+            }
         } else {
             self.complex_scorer::<DoNothingCombiner>(reader, boost)
                 .map(Into::into)
@@ -164,14 +332,33 @@ impl Weight for BooleanWeight {
             return Ok(Explanation::new("BooleanQuery with no scoring", 1f32));
         }
 
-        let mut explanation = Explanation::new("BooleanClause. Sum of ...", scorer.score());
+        let mut num_clauses = 0;
+        let mut child_explanations = Vec::new();
         for &(ref occur, ref subweight) in &self.weights {
             if is_positive_occur(*occur) {
+                num_clauses += 1;
                 if let Ok(child_explanation) = subweight.explain(reader, doc) {
-                    explanation.add_detail(child_explanation);
+                    child_explanations.push(child_explanation);
                 }
             }
         }
+        // No coordination factor is applied: the score is the plain
+        // combination of the scores of the matching clauses.
+        let description = match self.combiner {
+            CombinerKind::SumWithCoords | CombinerKind::Sum => format!(
+                "BooleanClause. {} ..., {} of {} clauses matching",
+                self.combiner.description(),
+                child_explanations.len(),
+                num_clauses
+            ),
+            CombinerKind::Max | CombinerKind::None => {
+                format!("BooleanClause. {} ...", self.combiner.description())
+            }
+        };
+        let mut explanation = Explanation::new(description, scorer.score());
+        for child_explanation in child_explanations {
+            explanation.add_detail(child_explanation);
+        }
         Ok(explanation)
     }
 
@@ -180,16 +367,16 @@ impl Weight for BooleanWeight {
         reader: &SegmentReader,
         callback: &mut dyn FnMut(DocId, Score),
     ) -> crate::Result<()> {
-        let scorer = self.complex_scorer::<SumWithCoordsCombiner>(reader, 1.0f32)?;
-        match scorer {
-            SpecializedScorer::TermUnion(mut union_scorer) => {
-                for_each_scorer(&mut union_scorer, callback);
+        match self.combiner {
+            CombinerKind::SumWithCoords => {
+                self.for_each_with_combiner::<SumWithCoordsCombiner>(reader, callback)
             }
-            SpecializedScorer::Other(mut scorer) => {
-                for_each_scorer(scorer.as_mut(), callback);
+            CombinerKind::Sum => self.for_each_with_combiner::<SumCombiner>(reader, callback),
+            CombinerKind::Max => self.for_each_with_combiner::<MaxScoreCombiner>(reader, callback),
+            CombinerKind::None => {
+                self.for_each_with_combiner::<DoNothingCombiner>(reader, callback)
             }
         }
-        Ok(())
     }
 
     /// Calls `callback` with all of the `(doc, score)` for which score
@@ -208,16 +395,20 @@ impl Weight for BooleanWeight {
         reader: &SegmentReader,
         callback: &mut dyn FnMut(DocId, Score) -> Score,
     ) -> crate::Result<()> {
-        let scorer = self.complex_scorer::<SumWithCoordsCombiner>(reader, 1.0f32)?;
-        match scorer {
-            SpecializedScorer::TermUnion(mut union_scorer) => {
-                for_each_pruning_scorer(&mut union_scorer, threshold, callback);
+        match self.combiner {
+            CombinerKind::SumWithCoords => self
+                .for_each_pruning_with_combiner::<SumWithCoordsCombiner>(
+                    threshold, reader, callback,
+                ),
+            CombinerKind::Sum => {
+                self.for_each_pruning_with_combiner::<SumCombiner>(threshold, reader, callback)
             }
-            SpecializedScorer::Other(mut scorer) => {
-                for_each_pruning_scorer(scorer.as_mut(), threshold, callback);
+            CombinerKind::Max => {
+                self.for_each_pruning_with_combiner::<MaxScoreCombiner>(threshold, reader, callback)
             }
+            CombinerKind::None => self
+                .for_each_pruning_with_combiner::<DoNothingCombiner>(threshold, reader, callback),
         }
-        Ok(())
     }
 }
 
@@ -1,26 +1,35 @@
+mod block_wand;
 mod boolean_query;
 mod boolean_weight;
 
 pub use self::boolean_query::BooleanQuery;
+pub(crate) use self::boolean_weight::scorer_union;
+#[cfg(test)]
+pub(crate) use self::boolean_weight::BooleanWeight;
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
     use crate::collector::tests::TEST_COLLECTOR_WITH_SCORE;
+    use crate::collector::TopDocs;
+    use crate::query::phrase_query::tests::create_index;
     use crate::query::score_combiner::SumWithCoordsCombiner;
     use crate::query::term_query::TermScorer;
+    use crate::query::CombinerKind;
     use crate::query::Intersection;
     use crate::query::Occur;
+    use crate::query::PhraseQuery;
     use crate::query::Query;
     use crate::query::QueryParser;
     use crate::query::RequiredOptionalScorer;
     use crate::query::Scorer;
     use crate::query::TermQuery;
+    use crate::query::TwoPhaseIntersection;
     use crate::schema::*;
     use crate::tests::assert_nearly_equals;
     use crate::Index;
-    use crate::{DocAddress, DocId};
+    use crate::{DocAddress, DocId, DocSet, TERMINATED};
 
     fn aux_test_helper() -> (Index, Field) {
         let mut schema_builder = Schema::builder();
@@ -177,6 +186,107 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_boolean_query_minimum_should_match() {
+        let (index, text_field) = aux_test_helper();
+
+        let make_term_query = |text: &str| {
+            let term_query = TermQuery::new(
+                Term::from_field_text(text_field, text),
+                IndexRecordOption::Basic,
+            );
+            let query: Box<dyn Query> = Box::new(term_query);
+            query
+        };
+
+        let reader = index.reader().unwrap();
+        let searcher = reader.searcher();
+
+        let matching_docs = |boolean_query: &dyn Query| {
+            let docs = searcher
+                .search(boolean_query, &TEST_COLLECTOR_WITH_SCORE)
+                .unwrap()
+                .docs()
+                .iter()
+                .cloned()
+                .map(|doc| doc.1)
+                .collect::<Vec<DocId>>();
+            assert_eq!(boolean_query.count(&searcher).unwrap(), docs.len());
+            docs
+        };
+
+        let should_abcd = || {
+            BooleanQuery::from(vec![
+                (Occur::Should, make_term_query("a")),
+                (Occur::Should, make_term_query("b")),
+                (Occur::Should, make_term_query("c")),
+                (Occur::Should, make_term_query("d")),
+            ])
+        };
+        assert_eq!(
+            matching_docs(&should_abcd().with_minimum_should_match(1)),
+            matching_docs(&should_abcd())
+        );
+        assert_eq!(
+            matching_docs(&should_abcd().with_minimum_should_match(1)),
+            vec![0, 1, 2, 3, 4]
+        );
+        assert_eq!(
+            matching_docs(&should_abcd().with_minimum_should_match(2)),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(
+            matching_docs(&should_abcd().with_minimum_should_match(3)),
+            vec![0, 3]
+        );
+        assert_eq!(
+            matching_docs(&should_abcd().with_minimum_should_match(5)),
+            Vec::<DocId>::new()
+        );
+        {
+            let boolean_query = BooleanQuery::from(vec![
+                (Occur::Must, make_term_query("c")),
+                (Occur::Should, make_term_query("a")),
+                (Occur::Should, make_term_query("b")),
+                (Occur::Should, make_term_query("d")),
+            ]);
+            assert_eq!(
+                matching_docs(&boolean_query.clone().with_minimum_should_match(1)),
+                vec![0, 1, 2, 3]
+            );
+            assert_eq!(
+                matching_docs(&boolean_query.clone().with_minimum_should_match(2)),
+                vec![0, 3]
+            );
+            assert_eq!(
+                matching_docs(&boolean_query.clone().with_minimum_should_match(3)),
+                vec![3]
+            );
+            assert_eq!(
+                matching_docs(&boolean_query.with_minimum_should_match(4)),
+                Vec::<DocId>::new()
+            );
+        }
+        {
+            // The `Must` clauses do not count toward the threshold, and a
+            // threshold of 1 leaves the `Should` clauses optional.
+            let boolean_query = BooleanQuery::from(vec![
+                (Occur::Must, make_term_query("d")),
+                (Occur::Should, make_term_query("a")),
+                (Occur::Should, make_term_query("b")),
+            ]);
+            assert_eq!(matching_docs(&boolean_query), vec![3, 4]);
+            assert_eq!(
+                matching_docs(&boolean_query.clone().with_minimum_should_match(1)),
+                vec![3, 4]
+            );
+            assert_eq!(
+                matching_docs(&boolean_query.with_minimum_should_match(2)),
+                vec![3]
+            );
+        }
+    }
+
     #[test]
     pub fn test_boolean_query_with_weight() {
         let mut schema_builder = Schema::builder();
@@ -219,6 +329,68 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_boolean_must_term_and_phrase_ordering() {
+        let index = create_index(&["a b c", "a c b", "b a c", "a b", "c a b d", "c"]);
+        let text_field = index.schema().get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let term_query: Box<dyn Query> = Box::new(TermQuery::new(
+            Term::from_field_text(text_field, "c"),
+            IndexRecordOption::WithFreqs,
+        ));
+        let phrase_query: Box<dyn Query> = Box::new(PhraseQuery::new(vec![
+            Term::from_field_text(text_field, "a"),
+            Term::from_field_text(text_field, "b"),
+        ]));
+        let segment_reader = searcher.segment_reader(0u32);
+        let scorer = |query: &dyn Query| {
+            query
+                .weight(&searcher, true)
+                .unwrap()
+                .scorer(segment_reader, 1.0f32)
+                .unwrap()
+        };
+        let mut term_scorer = scorer(term_query.as_ref());
+        let mut phrase_scorer = scorer(phrase_query.as_ref());
+        assert!(term_scorer.two_phase().is_none());
+        assert!(phrase_scorer.two_phase().unwrap().match_cost() > 0f32);
+
+        // Reference: a plain intersection, driven by the phrase scorer.
+        let mut expected_docs = Vec::new();
+        let mut expected_scores = Vec::new();
+        let mut unsorted_intersection =
+            Intersection::new_unsorted(vec![phrase_scorer, term_scorer]);
+        while unsorted_intersection.doc() != TERMINATED {
+            expected_docs.push(DocAddress(0, unsorted_intersection.doc()));
+            expected_scores.push(unsorted_intersection.score());
+            unsorted_intersection.advance();
+        }
+        assert_eq!(expected_docs, vec![DocAddress(0, 0), DocAddress(0, 4)]);
+
+        for subqueries in vec![
+            vec![
+                (Occur::Must, term_query.box_clone()),
+                (Occur::Must, phrase_query.box_clone()),
+            ],
+            vec![
+                (Occur::Must, phrase_query.box_clone()),
+                (Occur::Must, term_query.box_clone()),
+            ],
+        ] {
+            let boolean_query = BooleanQuery::from(subqueries);
+            let mut boolean_scorer = scorer(&boolean_query);
+            assert!(boolean_scorer.is::<TwoPhaseIntersection<Box<dyn Scorer>>>());
+            assert_eq!(boolean_scorer.two_phase().unwrap().match_cost(), 2f32);
+            let fruit = searcher
+                .search(&boolean_query, &TEST_COLLECTOR_WITH_SCORE)
+                .unwrap();
+            assert_eq!(fruit.docs(), &expected_docs[..]);
+            for (&score, &expected_score) in fruit.scores().iter().zip(&expected_scores) {
+                assert_nearly_equals(expected_score, score);
+            }
+        }
+    }
+
     #[test]
     pub fn test_intersection_score() {
         let (index, text_field) = aux_test_helper();
@@ -249,6 +421,235 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_boolean_explain_matching_clauses() {
+        let (index, text_field) = aux_test_helper();
+        let make_term_query = |text: &str| {
+            let term_query = TermQuery::new(
+                Term::from_field_text(text_field, text),
+                IndexRecordOption::WithFreqs,
+            );
+            let query: Box<dyn Query> = Box::new(term_query);
+            query
+        };
+        let searcher = index.reader().unwrap().searcher();
+        let boolean_query = BooleanQuery::from(vec![
+            (Occur::Must, make_term_query("a")),
+            (Occur::Should, make_term_query("b")),
+            (Occur::Should, make_term_query("d")),
+            (Occur::MustNot, make_term_query("e")),
+        ]);
+        let check_explanation = |doc: DocId, num_matching_clauses: usize| {
+            let explanation = boolean_query
+                .explain(&searcher, DocAddress(0, doc))
+                .unwrap();
+            let json: serde_json::Value =
+                serde_json::from_str(&explanation.to_pretty_json()).unwrap();
+            assert_eq!(
+                json["description"],
+                format!(
+                    "BooleanClause. Sum of ..., {} of 3 clauses matching",
+                    num_matching_clauses
+                )
+            );
+            // The details are the matching clauses, and sum up to the score.
+            let details = json["details"].as_array().unwrap();
+            assert_eq!(details.len(), num_matching_clauses);
+            let clauses_sum: f32 = details
+                .iter()
+                .map(|detail| detail["value"].as_f64().unwrap() as f32)
+                .sum();
+            assert_nearly_equals(clauses_sum, explanation.value());
+        };
+        // "a c" only matches the required clause.
+        check_explanation(1, 1);
+        check_explanation(0, 2);
+        check_explanation(3, 3);
+
+        let weight = boolean_query.weight(&searcher, false).unwrap();
+        let explanation = weight.explain(searcher.segment_reader(0u32), 1).unwrap();
+        assert!(!explanation.to_pretty_json().contains("clauses matching"));
+    }
+
+    #[test]
+    pub fn test_boolean_same_subquery_in_several_occurs() {
+        let index = create_index(&["a b", "a", "b", "a c", "c"]);
+        let text_field = index.schema().get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let term_a: Box<dyn Query> = Box::new(TermQuery::new(
+            Term::from_field_text(text_field, "a"),
+            IndexRecordOption::WithFreqs,
+        ));
+        let term_b: Box<dyn Query> = Box::new(TermQuery::new(
+            Term::from_field_text(text_field, "b"),
+            IndexRecordOption::WithFreqs,
+        ));
+        let term_c: Box<dyn Query> = Box::new(TermQuery::new(
+            Term::from_field_text(text_field, "c"),
+            IndexRecordOption::WithFreqs,
+        ));
+        let score = |query: &dyn Query, doc: DocId| {
+            query
+                .explain(&searcher, DocAddress(0, doc))
+                .map(|explanation| explanation.value())
+                .unwrap_or(0f32)
+        };
+        let scored_docs = |query: &dyn Query| {
+            let mut scorer = query
+                .weight(&searcher, true)
+                .unwrap()
+                .scorer(segment_reader, 1.0f32)
+                .unwrap();
+            let mut scored_docs = Vec::new();
+            while scorer.doc() != TERMINATED {
+                scored_docs.push((scorer.doc(), scorer.score()));
+                scorer.advance();
+            }
+            scored_docs
+        };
+        // Each occurrence of a query in the tree gets its own scorer.
+        let reqopt_query = BooleanQuery::from(vec![
+            (Occur::Must, term_a.box_clone()),
+            (Occur::Should, term_a.box_clone()),
+            (Occur::Should, term_b.box_clone()),
+        ]);
+        let reqopt_docs = scored_docs(&reqopt_query);
+        assert_eq!(
+            reqopt_docs.iter().map(|&(doc, _)| doc).collect::<Vec<_>>(),
+            vec![0, 1, 3]
+        );
+        for &(doc, doc_score) in &reqopt_docs {
+            let expected_score = 2f32 * score(term_a.as_ref(), doc) + score(term_b.as_ref(), doc);
+            assert_nearly_equals(expected_score, doc_score);
+        }
+        let nested_query = BooleanQuery::from(vec![
+            (Occur::Should, term_a.box_clone()),
+            (
+                Occur::Must,
+                Box::new(BooleanQuery::from(vec![
+                    (Occur::Should, term_a.box_clone()),
+                    (Occur::Should, term_c.box_clone()),
+                ])),
+            ),
+        ]);
+        let nested_docs = scored_docs(&nested_query);
+        assert_eq!(
+            nested_docs.iter().map(|&(doc, _)| doc).collect::<Vec<_>>(),
+            vec![0, 1, 3, 4]
+        );
+        assert_nearly_equals(2f32 * score(term_a.as_ref(), 1), nested_docs[1].1);
+    }
+
+    #[test]
+    pub fn test_boolean_combiner_kind() {
+        let (index, text_field) = aux_test_helper();
+        let searcher = index.reader().unwrap().searcher();
+        let term_query = |text: &str| -> Box<dyn Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(text_field, text),
+                IndexRecordOption::WithFreqs,
+            ))
+        };
+        let score = |query: &dyn Query, doc: DocId| {
+            query
+                .explain(&searcher, DocAddress(0u32, doc))
+                .unwrap()
+                .value()
+        };
+        let search_scores = |query: &BooleanQuery| {
+            let test_fruits = searcher.search(query, &TEST_COLLECTOR_WITH_SCORE).unwrap();
+            let top_docs = searcher.search(query, &TopDocs::with_limit(10)).unwrap();
+            for &(top_score, doc_address) in &top_docs {
+                let ord = test_fruits
+                    .docs()
+                    .iter()
+                    .position(|&doc| doc == doc_address)
+                    .unwrap();
+                assert_nearly_equals(test_fruits.scores()[ord], top_score);
+                assert_nearly_equals(top_score, score(query, doc_address.doc()));
+            }
+            test_fruits
+                .docs()
+                .iter()
+                .map(|doc| doc.doc())
+                .zip(test_fruits.scores().iter().cloned())
+                .collect::<Vec<(DocId, f32)>>()
+        };
+        let (a, b, c) = (term_query("a"), term_query("b"), term_query("c"));
+        let (score_a, score_b, score_c) = (
+            score(a.as_ref(), 0),
+            score(b.as_ref(), 0),
+            score(c.as_ref(), 0),
+        );
+        {
+            let should_query = BooleanQuery::from(vec![
+                (Occur::Should, a.box_clone()),
+                (Occur::Should, b.box_clone()),
+            ]);
+            let sum_with_coords_scores = search_scores(&should_query);
+            let sum_scores = search_scores(&should_query.clone().with_combiner(CombinerKind::Sum));
+            let max_scores = search_scores(&should_query.clone().with_combiner(CombinerKind::Max));
+            assert_eq!(sum_with_coords_scores[0].0, 0);
+            assert_nearly_equals(score_a + score_b, sum_scores[0].1);
+            assert_nearly_equals(score_a.max(score_b), max_scores[0].1);
+            assert!(max_scores[0].1 < sum_scores[0].1);
+            // No coordination factor is applied: both sums are the same.
+            assert_nearly_equals(sum_scores[0].1, sum_with_coords_scores[0].1);
+            // Doc 1 only matches `a`.
+            assert_eq!(max_scores[1].0, 1);
+            assert_nearly_equals(sum_scores[1].1, max_scores[1].1);
+            let none_scores =
+                search_scores(&should_query.clone().with_combiner(CombinerKind::None));
+            assert_eq!(none_scores.len(), sum_scores.len());
+            assert!(none_scores.iter().all(|&(_, score)| score == 1f32));
+        }
+        {
+            let required_optional_query = BooleanQuery::from(vec![
+                (Occur::Must, c.box_clone()),
+                (Occur::Should, a.box_clone()),
+                (Occur::Should, b.box_clone()),
+            ]);
+            let sum_scores = search_scores(
+                &required_optional_query
+                    .clone()
+                    .with_combiner(CombinerKind::Sum),
+            );
+            let max_scores = search_scores(
+                &required_optional_query
+                    .clone()
+                    .with_combiner(CombinerKind::Max),
+            );
+            assert_eq!(sum_scores[0].0, 0);
+            assert_nearly_equals(score_a + score_b + score_c, sum_scores[0].1);
+            assert_nearly_equals(score_a.max(score_b).max(score_c), max_scores[0].1);
+        }
+        let should_query = BooleanQuery::from(vec![(Occur::Should, a), (Occur::Should, b)]);
+        let description = |combiner: CombinerKind| {
+            let explanation = should_query
+                .clone()
+                .with_combiner(combiner)
+                .explain(&searcher, DocAddress(0u32, 0u32))
+                .unwrap();
+            let json: serde_json::Value =
+                serde_json::from_str(&explanation.to_pretty_json()).unwrap();
+            json["description"].as_str().unwrap().to_string()
+        };
+        assert_eq!(
+            description(CombinerKind::SumWithCoords),
+            "BooleanClause. Sum of ..., 2 of 2 clauses matching"
+        );
+        assert_eq!(
+            description(CombinerKind::Sum),
+            "BooleanClause. Sum of ..., 2 of 2 clauses matching"
+        );
+        assert_eq!(description(CombinerKind::Max), "BooleanClause. Max of ...");
+        assert_eq!(
+            description(CombinerKind::None),
+            "BooleanClause. Constant score regardless of ..."
+        );
+    }
+
     // motivated by #554
     #[test]
     fn test_bm25_several_fields() {
@@ -299,11 +700,11 @@ mod tests {
             explanation.to_pretty_json(),
             r#"{
   "value": 12.997711,
-  "description": "BooleanClause. Sum of ...",
+  "description": "BooleanClause. Sum of ..., 1 of 2 clauses matching",
   "details": [
     {
       "value": 12.997711,
-      "description": "BooleanClause. Sum of ...",
+      "description": "BooleanClause. Sum of ..., 2 of 2 clauses matching",
       "details": [
         {
           "value": 6.551476,
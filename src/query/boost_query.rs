@@ -1,6 +1,6 @@
 use crate::fastfield::DeleteBitSet;
 use crate::query::explanation::does_not_match;
-use crate::query::{Explanation, Query, Scorer, Weight};
+use crate::query::{Explanation, Query, Scorer, TwoPhase, Weight};
 use crate::{DocId, DocSet, Searcher, SegmentReader, Term};
 use std::collections::BTreeSet;
 use std::fmt;
@@ -15,9 +15,22 @@ pub struct BoostQuery {
     boost: f32,
 }
 
+fn assert_valid_boost(boost: f32) {
+    assert!(
+        boost >= 0f32,
+        "The boost is required to be a non-negative number, got {}.",
+        boost
+    );
+}
+
 impl BoostQuery {
     /// Builds a boost query.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `boost` is negative or NaN.
     pub fn new(query: Box<dyn Query>, boost: f32) -> BoostQuery {
+        assert_valid_boost(boost);
         BoostQuery { query, boost }
     }
 }
@@ -40,7 +53,9 @@ impl fmt::Debug for BoostQuery {
 impl Query for BoostQuery {
     fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
         let weight_without_boost = self.query.weight(searcher, scoring_enabled)?;
-        let boosted_weight = if scoring_enabled {
+        // A boost of 1 leaves the scores untouched.
+        let is_passthrough = (self.boost - 1f32).abs() < f32::EPSILON;
+        let boosted_weight = if scoring_enabled && !is_passthrough {
             Box::new(BoostWeight::new(weight_without_boost, self.boost))
         } else {
             weight_without_boost
@@ -53,13 +68,24 @@ impl Query for BoostQuery {
     }
 }
 
-pub(crate) struct BoostWeight {
+/// `BoostWeight` wraps a `Weight` and multiplies the score
+/// of its scorers by a `boost` factor.
+///
+/// The boost is passed down to the underlying weight, as the `boost`
+/// argument of `Weight::scorer`.
+pub struct BoostWeight {
     weight: Box<dyn Weight>,
     boost: f32,
 }
 
 impl BoostWeight {
+    /// Creates a new `BoostWeight`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `boost` is negative or NaN.
     pub fn new(weight: Box<dyn Weight>, boost: f32) -> Self {
+        assert_valid_boost(boost);
         BoostWeight { weight, boost }
     }
 }
@@ -76,6 +102,7 @@ impl Weight for BoostWeight {
         }
         let mut explanation =
             Explanation::new(format!("Boost x{} of ...", self.boost), scorer.score());
+        explanation.add_const("Boost factor", self.boost);
         let underlying_explanation = self.weight.explain(reader, doc)?;
         explanation.add_detail(underlying_explanation);
         Ok(explanation)
@@ -125,6 +152,10 @@ impl<S: Scorer> DocSet for BoostScorer<S> {
     fn count_including_deleted(&mut self) -> u32 {
         self.underlying.count_including_deleted()
     }
+
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        self.underlying.two_phase()
+    }
 }
 
 impl<S: Scorer> Scorer for BoostScorer<S> {
@@ -135,10 +166,11 @@ impl<S: Scorer> Scorer for BoostScorer<S> {
 
 #[cfg(test)]
 mod tests {
-    use super::BoostQuery;
-    use crate::query::{AllQuery, Query};
-    use crate::schema::Schema;
-    use crate::{DocAddress, Document, Index};
+    use super::{BoostQuery, BoostWeight};
+    use crate::query::{AllQuery, Query, TermQuery, Weight};
+    use crate::schema::{IndexRecordOption, Schema, TEXT};
+    use crate::tests::assert_nearly_equals;
+    use crate::{DocAddress, DocSet, Document, Index, Term};
 
     #[test]
     fn test_boost_query_explain() {
@@ -153,7 +185,83 @@ mod tests {
         let explanation = query.explain(&searcher, DocAddress(0, 0u32)).unwrap();
         assert_eq!(
             explanation.to_pretty_json(),
-            "{\n  \"value\": 0.2,\n  \"description\": \"Boost x0.2 of ...\",\n  \"details\": [\n    {\n      \"value\": 1.0,\n      \"description\": \"AllQuery\"\n    }\n  ]\n}"
+            "{\n  \"value\": 0.2,\n  \"description\": \"Boost x0.2 of ...\",\n  \"details\": [\n    {\n      \"value\": 0.2,\n      \"description\": \"Boost factor\"\n    },\n    {\n      \"value\": 1.0,\n      \"description\": \"AllQuery\"\n    }\n  ]\n}"
         )
     }
+
+    fn term_query_score(boost: f32) -> f32 {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(doc!(text_field=>"a b c"));
+        index_writer.add_document(doc!(text_field=>"a a d"));
+        assert!(index_writer.commit().is_ok());
+        let searcher = index.reader().unwrap().searcher();
+        let term_query = TermQuery::new(
+            Term::from_field_text(text_field, "a"),
+            IndexRecordOption::WithFreqs,
+        );
+        let query = BoostQuery::new(Box::new(term_query), boost);
+        query
+            .explain(&searcher, DocAddress(0, 1u32))
+            .unwrap()
+            .value()
+    }
+
+    #[test]
+    fn test_boost_query_scales_linearly() {
+        let score = term_query_score(1f32);
+        assert!(score > 0f32);
+        assert_eq!(term_query_score(0f32), 0f32);
+        assert_nearly_equals(term_query_score(0.5f32), 0.5f32 * score);
+        assert_nearly_equals(term_query_score(2f32), 2f32 * score);
+        assert_nearly_equals(term_query_score(3f32), 3f32 * score);
+    }
+
+    #[test]
+    fn test_boost_query_one_is_passthrough() {
+        let schema = Schema::builder().build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(Document::new());
+        assert!(index_writer.commit().is_ok());
+        let searcher = index.reader().unwrap().searcher();
+        let query = BoostQuery::new(Box::new(AllQuery), 1f32);
+        let explanation = query.explain(&searcher, DocAddress(0, 0u32)).unwrap();
+        let all_explanation = AllQuery.explain(&searcher, DocAddress(0, 0u32)).unwrap();
+        assert_eq!(
+            explanation.to_pretty_json(),
+            all_explanation.to_pretty_json()
+        );
+    }
+
+    #[test]
+    fn test_boost_weight() {
+        let schema = Schema::builder().build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+        index_writer.add_document(Document::new());
+        assert!(index_writer.commit().is_ok());
+        let searcher = index.reader().unwrap().searcher();
+        let all_weight = AllQuery.weight(&searcher, true).unwrap();
+        let boost_weight = BoostWeight::new(all_weight, 3f32);
+        let mut scorer = boost_weight
+            .scorer(searcher.segment_reader(0u32), 2f32)
+            .unwrap();
+        assert_eq!(scorer.doc(), 0u32);
+        assert_nearly_equals(scorer.score(), 6f32);
+    }
+
+    #[test]
+    #[should_panic(expected = "The boost is required to be a non-negative number, got -1.")]
+    fn test_boost_query_negative_boost() {
+        BoostQuery::new(Box::new(AllQuery), -1f32);
+    }
+
+    #[test]
+    #[should_panic(expected = "The boost is required to be a non-negative number")]
+    fn test_boost_query_nan_boost() {
+        BoostQuery::new(Box::new(AllQuery), std::f32::NAN);
+    }
 }
@@ -0,0 +1,295 @@
+use crate::core::SegmentReader;
+use crate::docset::DocSet;
+use crate::query::boolean_query::scorer_union;
+use crate::query::explanation::does_not_match;
+use crate::query::score_combiner::{DoNothingCombiner, MaxScoreCombiner};
+use crate::query::{EmptyScorer, Explanation, Query, Scorer, Weight};
+use crate::{DocId, Score, Searcher, Term};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// `DisjunctionMaxQuery` matches the union of its sub-queries, called disjuncts.
+///
+/// Rather than summing the scores of the matching disjuncts, the score
+/// of a document is the maximum of the scores of its matching disjuncts,
+/// plus `tie_breaker` times the sum of the scores of the other
+/// matching disjuncts.
+///
+/// This is typically useful when searching the same terms in several fields:
+/// a document matching a term in one field is not outscored by a document
+/// matching the term in several fields with lower scores.
+///
+/// A `tie_breaker` of `0.0` gives pure max scoring, while a `tie_breaker` of
+/// `1.0` gives the same scores as a disjunction `BooleanQuery`.
+///
+/// ```rust
+/// use tantivy::collector::TopDocs;
+/// use tantivy::query::{DisjunctionMaxQuery, Query, TermQuery};
+/// use tantivy::schema::{IndexRecordOption, Schema, TEXT};
+/// use tantivy::{doc, Index, Term};
+/// # fn test() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let body = schema_builder.add_text_field("body", TEXT);
+/// let schema = schema_builder.build();
+/// let index = Index::create_in_ram(schema);
+/// {
+///     let mut index_writer = index.writer(3_000_000)?;
+///     index_writer.add_document(doc!(title => "The Diary of Muadib", body => "A diary"));
+///     index_writer.add_document(doc!(title => "A Dairy Cow"));
+///     index_writer.commit()?;
+/// }
+/// let searcher = index.reader()?.searcher();
+/// let diary_in_title: Box<dyn Query> = Box::new(TermQuery::new(
+///     Term::from_field_text(title, "diary"),
+///     IndexRecordOption::WithFreqs,
+/// ));
+/// let diary_in_body: Box<dyn Query> = Box::new(TermQuery::new(
+///     Term::from_field_text(body, "diary"),
+///     IndexRecordOption::WithFreqs,
+/// ));
+/// let query = DisjunctionMaxQuery::new(vec![diary_in_title, diary_in_body], 0.1);
+/// let top_docs = searcher.search(&query, &TopDocs::with_limit(2))?;
+/// assert_eq!(top_docs.len(), 1);
+/// Ok(())
+/// # }
+/// # assert!(test().is_ok());
+/// ```
+pub struct DisjunctionMaxQuery {
+    disjuncts: Vec<Box<dyn Query>>,
+    tie_breaker: Score,
+}
+
+impl DisjunctionMaxQuery {
+    /// Creates a new `DisjunctionMaxQuery`.
+    pub fn new(disjuncts: Vec<Box<dyn Query>>, tie_breaker: Score) -> DisjunctionMaxQuery {
+        DisjunctionMaxQuery {
+            disjuncts,
+            tie_breaker,
+        }
+    }
+}
+
+impl Clone for DisjunctionMaxQuery {
+    fn clone(&self) -> Self {
+        DisjunctionMaxQuery {
+            disjuncts: self
+                .disjuncts
+                .iter()
+                .map(|disjunct| disjunct.box_clone())
+                .collect(),
+            tie_breaker: self.tie_breaker,
+        }
+    }
+}
+
+impl fmt::Debug for DisjunctionMaxQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "DisjunctionMax(disjuncts={:?}, tie_breaker={})",
+            self.disjuncts, self.tie_breaker
+        )
+    }
+}
+
+impl Query for DisjunctionMaxQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        let weights = self
+            .disjuncts
+            .iter()
+            .map(|disjunct| disjunct.weight(searcher, scoring_enabled))
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(Box::new(DisMaxWeight {
+            weights,
+            tie_breaker: self.tie_breaker,
+            scoring_enabled,
+        }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for disjunct in &self.disjuncts {
+            disjunct.query_terms(term_set);
+        }
+    }
+}
+
+struct DisMaxWeight {
+    weights: Vec<Box<dyn Weight>>,
+    tie_breaker: Score,
+    scoring_enabled: bool,
+}
+
+impl Weight for DisMaxWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: f32) -> crate::Result<Box<dyn Scorer>> {
+        if self.weights.is_empty() {
+            return Ok(Box::new(EmptyScorer));
+        }
+        let scorers = self
+            .weights
+            .iter()
+            .map(|weight| weight.scorer(reader, boost))
+            .collect::<crate::Result<Vec<_>>>()?;
+        if self.scoring_enabled {
+            let tie_breaker = self.tie_breaker;
+            Ok(scorer_union(scorers, || MaxScoreCombiner::with_tie_breaker(tie_breaker)).into())
+        } else {
+            Ok(scorer_union(scorers, DoNothingCombiner::default).into())
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.scorer(reader, 1.0f32)?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        if !self.scoring_enabled {
+            return Ok(Explanation::new(
+                "DisjunctionMaxQuery with no scoring",
+                1f32,
+            ));
+        }
+        let mut matching_explanations: Vec<(usize, Explanation)> = self
+            .weights
+            .iter()
+            .enumerate()
+            .filter_map(|(ord, weight)| {
+                weight
+                    .explain(reader, doc)
+                    .ok()
+                    .map(|explanation| (ord, explanation))
+            })
+            .collect();
+        let mut explanation = Explanation::new(
+            format!(
+                "DisjunctionMaxQuery. Max of ... plus {} x the sum of the others",
+                self.tie_breaker
+            ),
+            scorer.score(),
+        );
+        // `max_by` returns the last maximum, we want the first one.
+        let max_pos = matching_explanations
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by(|(_, (_, left)), (_, (_, right))| {
+                left.value()
+                    .partial_cmp(&right.value())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(pos, _)| pos);
+        if let Some(max_pos) = max_pos {
+            let (max_ord, max_explanation) = matching_explanations.remove(max_pos);
+            let mut max_detail = Explanation::new(
+                format!("Max, from disjunct #{}", max_ord),
+                max_explanation.value(),
+            );
+            max_detail.add_detail(max_explanation);
+            explanation.add_detail(max_detail);
+        }
+        if !matching_explanations.is_empty() {
+            let others_sum: Score = matching_explanations
+                .iter()
+                .map(|(_, explanation)| explanation.value())
+                .sum();
+            let mut tie_breaker_detail = Explanation::new(
+                format!(
+                    "Tie breaker x{} of the sum of the other disjuncts",
+                    self.tie_breaker
+                ),
+                self.tie_breaker * others_sum,
+            );
+            for (_, other_explanation) in matching_explanations {
+                tie_breaker_detail.add_detail(other_explanation);
+            }
+            explanation.add_detail(tie_breaker_detail);
+        }
+        Ok(explanation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DisjunctionMaxQuery;
+    use crate::query::{BooleanQuery, Occur, Query, TermQuery};
+    use crate::schema::{Field, IndexRecordOption, Schema, TEXT};
+    use crate::tests::assert_nearly_equals;
+    use crate::{DocAddress, Index, Score, Searcher, Term};
+
+    fn create_index() -> (Index, Field, Field) {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(title=>"a b", body=>"a a c"));
+            index_writer.add_document(doc!(title=>"a", body=>"c"));
+            index_writer.add_document(doc!(title=>"b", body=>"a b c d"));
+            assert!(index_writer.commit().is_ok());
+        }
+        (index, title, body)
+    }
+
+    fn term_query(field: Field, text: &str) -> Box<dyn Query> {
+        Box::new(TermQuery::new(
+            Term::from_field_text(field, text),
+            IndexRecordOption::WithFreqs,
+        ))
+    }
+
+    fn score(searcher: &Searcher, query: &dyn Query, doc: DocAddress) -> Score {
+        query.explain(searcher, doc).unwrap().value()
+    }
+
+    #[test]
+    fn test_disjunction_max_query_scores() {
+        let (index, title, body) = create_index();
+        let searcher = index.reader().unwrap().searcher();
+        let doc = DocAddress(0, 0);
+        let title_score = score(&searcher, term_query(title, "a").as_ref(), doc);
+        let body_score = score(&searcher, term_query(body, "a").as_ref(), doc);
+        assert!(body_score > title_score);
+        let dismax_score = |tie_breaker: Score| {
+            let query = DisjunctionMaxQuery::new(
+                vec![term_query(title, "a"), term_query(body, "a")],
+                tie_breaker,
+            );
+            let explanation_score = score(&searcher, &query, doc);
+            let weight = query.weight(&searcher, true).unwrap();
+            let mut scorer = weight.scorer(searcher.segment_reader(0), 1.0f32).unwrap();
+            assert_eq!(scorer.doc(), 0);
+            assert_nearly_equals(scorer.score(), explanation_score);
+            explanation_score
+        };
+        assert_nearly_equals(dismax_score(0.0), body_score);
+        assert_nearly_equals(dismax_score(0.3), body_score + 0.3 * title_score);
+        assert_nearly_equals(dismax_score(1.0), body_score + title_score);
+        let boolean_query = BooleanQuery::from(vec![
+            (Occur::Should, term_query(title, "a")),
+            (Occur::Should, term_query(body, "a")),
+        ]);
+        assert_nearly_equals(dismax_score(1.0), score(&searcher, &boolean_query, doc));
+    }
+
+    #[test]
+    fn test_disjunction_max_query_explain() {
+        let (index, title, body) = create_index();
+        let searcher = index.reader().unwrap().searcher();
+        let query =
+            DisjunctionMaxQuery::new(vec![term_query(title, "a"), term_query(body, "a")], 0.5);
+        assert_eq!(query.count(&searcher).unwrap(), 3);
+        let body_score = score(&searcher, term_query(body, "a").as_ref(), DocAddress(0, 2));
+        let explanation = query.explain(&searcher, DocAddress(0, 2)).unwrap();
+        assert_nearly_equals(explanation.value(), body_score);
+        let explanation_json = explanation.to_pretty_json();
+        assert!(explanation_json.contains("Max, from disjunct #1"));
+        assert!(!explanation_json.contains("Tie breaker"));
+        let explanation = query.explain(&searcher, DocAddress(0, 0)).unwrap();
+        let explanation_json = explanation.to_pretty_json();
+        assert!(explanation_json.contains("Max, from disjunct #1"));
+        assert!(explanation_json.contains("Tie breaker x0.5 of the sum of the other disjuncts"));
+        assert!(query.explain(&searcher, DocAddress(0, 1)).is_ok());
+    }
+}
@@ -0,0 +1,183 @@
+use crate::core::searcher::Searcher;
+use crate::core::SegmentReader;
+use crate::docset::DocSet;
+use crate::query::explanation::does_not_match;
+use crate::query::term_query::{TermScorer, TermWeight};
+use crate::query::{Explanation, Intersection, Query, Scorer, TermQuery, Weight};
+use crate::schema::{Field, IndexRecordOption, Term};
+use crate::{DocId, Score};
+use std::collections::BTreeSet;
+
+/// `DualFieldQuery` matches the documents containing a term in two
+/// different fields, and scores them by the lowest of the two
+/// BM25 scores of the term.
+///
+/// For instance, a `DualFieldQuery` for the word `"tantivy"` on
+/// the `title` and `body` fields only matches the documents
+/// mentioning tantivy both in their title and their body.
+/// A document with a very good score in only one of the fields
+/// gets ranked after a document scoring reasonably well in both.
+///
+/// The score of each field is the score of a `TermQuery` on this field.
+#[derive(Clone, Debug)]
+pub struct DualFieldQuery {
+    term_a: Term,
+    term_b: Term,
+}
+
+impl DualFieldQuery {
+    /// Creates a new `DualFieldQuery`.
+    ///
+    /// The field of `term` is ignored: its value is searched in
+    /// both `field_a` and `field_b`.
+    pub fn new(term: Term, field_a: Field, field_b: Field) -> DualFieldQuery {
+        let mut term_a = term.clone();
+        term_a.set_field(field_a);
+        let mut term_b = term;
+        term_b.set_field(field_b);
+        DualFieldQuery { term_a, term_b }
+    }
+}
+
+impl Query for DualFieldQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        let specialized_weight = |term: &Term| {
+            TermQuery::new(term.clone(), IndexRecordOption::WithFreqs)
+                .specialized_weight(searcher, scoring_enabled)
+        };
+        Ok(Box::new(DualFieldWeight {
+            weight_a: specialized_weight(&self.term_a),
+            weight_b: specialized_weight(&self.term_b),
+        }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        term_set.insert(self.term_a.clone());
+        term_set.insert(self.term_b.clone());
+    }
+}
+
+struct DualFieldWeight {
+    weight_a: TermWeight,
+    weight_b: TermWeight,
+}
+
+impl DualFieldWeight {
+    fn dual_field_scorer(
+        &self,
+        reader: &SegmentReader,
+        boost: f32,
+    ) -> crate::Result<DualFieldScorer> {
+        let scorer_a = self.weight_a.scorer_specialized(reader, boost)?;
+        let scorer_b = self.weight_b.scorer_specialized(reader, boost)?;
+        Ok(DualFieldScorer {
+            intersection: Intersection::new(vec![scorer_a, scorer_b]),
+        })
+    }
+}
+
+impl Weight for DualFieldWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: f32) -> crate::Result<Box<dyn Scorer>> {
+        Ok(Box::new(self.dual_field_scorer(reader, boost)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.dual_field_scorer(reader, 1.0f32)?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        let mut explanation = Explanation::new("DualFieldQuery. Min of ...", scorer.score());
+        explanation.add_detail(self.weight_a.explain(reader, doc)?);
+        explanation.add_detail(self.weight_b.explain(reader, doc)?);
+        Ok(explanation)
+    }
+}
+
+struct DualFieldScorer {
+    intersection: Intersection<TermScorer, TermScorer>,
+}
+
+impl DocSet for DualFieldScorer {
+    fn advance(&mut self) -> DocId {
+        self.intersection.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.intersection.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.intersection.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.intersection.size_hint()
+    }
+}
+
+impl Scorer for DualFieldScorer {
+    fn score(&mut self) -> Score {
+        let score_left = self.intersection.docset_mut_specialized(0).score();
+        let score_right = self.intersection.docset_mut_specialized(1).score();
+        score_left.min(score_right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DualFieldQuery;
+    use crate::collector::tests::TEST_COLLECTOR_WITH_SCORE;
+    use crate::query::{Query, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, TEXT};
+    use crate::tests::assert_nearly_equals;
+    use crate::{DocAddress, Index, Term};
+
+    #[test]
+    fn test_dual_field_query() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(title=>"tantivy", body=>"a search engine"));
+            index_writer.add_document(doc!(title=>"lucene", body=>"tantivy tantivy tantivy"));
+            index_writer.add_document(doc!(
+                title=>"tantivy, a search engine library",
+                body=>"tantivy is a search engine library written in rust"
+            ));
+            index_writer.add_document(doc!(title=>"tantivy", body=>"tantivy"));
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let query = DualFieldQuery::new(Term::from_field_text(title, "tantivy"), title, body);
+
+        let test_fruits = searcher.search(&query, &TEST_COLLECTOR_WITH_SCORE).unwrap();
+        assert_eq!(test_fruits.docs(), &[DocAddress(0, 2), DocAddress(0, 3)]);
+
+        let field_score = |field, doc| {
+            let term_query = TermQuery::new(
+                Term::from_field_text(field, "tantivy"),
+                IndexRecordOption::WithFreqs,
+            );
+            term_query
+                .explain(&searcher, DocAddress(0, doc))
+                .unwrap()
+                .value()
+        };
+        for (&doc, &score) in test_fruits.docs().iter().zip(test_fruits.scores()) {
+            let title_score = field_score(title, doc.doc());
+            let body_score = field_score(body, doc.doc());
+            assert_nearly_equals(title_score.min(body_score), score);
+        }
+        // Relatively to the average field lengths, the title of doc 2
+        // is longer than its body.
+        assert!(field_score(title, 2) < field_score(body, 2));
+        assert_nearly_equals(field_score(title, 2), test_fruits.scores()[0]);
+
+        let explanation = query.explain(&searcher, DocAddress(0, 2)).unwrap();
+        assert_nearly_equals(explanation.value(), test_fruits.scores()[0]);
+        assert!(query.explain(&searcher, DocAddress(0, 1)).is_err());
+    }
+}
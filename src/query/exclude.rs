@@ -1,14 +1,22 @@
 use crate::docset::{DocSet, TERMINATED};
-use crate::query::Scorer;
+use crate::query::{match_cost, Scorer, TwoPhase};
 use crate::DocId;
 use crate::Score;
 
 /// Filters a given `DocSet` by removing the docs from a given `DocSet`.
 ///
 /// The excluding docset has no impact on scoring.
+///
+/// If the excluding docset is a two-phase docset, only its approximation
+/// is iterated through. A document is removed only if `.matches()`
+/// confirms that the excluding docset actually contains it.
+///
+/// If either docset is a two-phase docset, `Exclude` is itself a two-phase
+/// docset, whose approximation is the one of the underlying docset.
 pub struct Exclude<TDocSet, TDocSetExclude> {
     underlying_docset: TDocSet,
     excluding_docset: TDocSetExclude,
+    match_cost: f32,
 }
 
 impl<TDocSet, TDocSetExclude> Exclude<TDocSet, TDocSetExclude>
@@ -21,18 +29,19 @@ where
         mut underlying_docset: TDocSet,
         mut excluding_docset: TDocSetExclude,
     ) -> Exclude<TDocSet, TDocSetExclude> {
-        while underlying_docset.doc() != TERMINATED {
-            let target = underlying_docset.doc();
-            if excluding_docset.seek(target) != target {
-                // this document is not excluded.
-                break;
-            }
-            underlying_docset.advance();
-        }
-        Exclude {
+        let match_cost = match_cost(&mut underlying_docset) + match_cost(&mut excluding_docset);
+        let mut exclude = Exclude {
             underlying_docset,
             excluding_docset,
+            match_cost,
+        };
+        while exclude.underlying_docset.doc() != TERMINATED {
+            if exclude.accept() {
+                break;
+            }
+            exclude.underlying_docset.advance();
         }
+        exclude
     }
 }
 
@@ -47,7 +56,26 @@ where
     /// increasing `doc`.
     fn accept(&mut self) -> bool {
         let doc = self.underlying_docset.doc();
-        self.excluding_docset.seek(doc) != doc
+        !self.is_excluded(doc)
+    }
+
+    /// Returns true iff the excluding docset contains `doc`.
+    ///
+    /// The method has to be called with non strictly
+    /// increasing `doc`.
+    fn is_excluded(&mut self, doc: DocId) -> bool {
+        if let Some(two_phase) = self.excluding_docset.two_phase() {
+            let approximation = two_phase.approximation();
+            if approximation.doc() < doc {
+                approximation.seek(doc);
+            }
+            approximation.doc() == doc && two_phase.matches()
+        } else {
+            if self.excluding_docset.doc() < doc {
+                self.excluding_docset.seek(doc);
+            }
+            self.excluding_docset.doc() == doc
+        }
     }
 }
 
@@ -86,6 +114,42 @@ where
     fn size_hint(&self) -> u32 {
         self.underlying_docset.size_hint()
     }
+
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        if self.underlying_docset.two_phase().is_none()
+            && self.excluding_docset.two_phase().is_none()
+        {
+            return None;
+        }
+        Some(self)
+    }
+}
+
+impl<TDocSet, TDocSetExclude> TwoPhase for Exclude<TDocSet, TDocSetExclude>
+where
+    TDocSet: DocSet,
+    TDocSetExclude: DocSet,
+{
+    fn approximation(&mut self) -> &mut dyn DocSet {
+        if self.underlying_docset.two_phase().is_some() {
+            return self.underlying_docset.two_phase().unwrap().approximation();
+        }
+        &mut self.underlying_docset
+    }
+
+    fn matches(&mut self) -> bool {
+        let doc = self.underlying_docset.doc();
+        if let Some(two_phase) = self.underlying_docset.two_phase() {
+            if !two_phase.matches() {
+                return false;
+            }
+        }
+        !self.is_excluded(doc)
+    }
+
+    fn match_cost(&self) -> f32 {
+        self.match_cost
+    }
 }
 
 impl<TScorer, TDocSetExclude> Scorer for Exclude<TScorer, TDocSetExclude>
@@ -102,9 +166,13 @@ where
 mod tests {
 
     use super::*;
+    use crate::collector::tests::TEST_COLLECTOR_WITHOUT_SCORE;
     use crate::postings::tests::test_skip_against_unoptimized;
-    use crate::query::VecDocSet;
+    use crate::query::phrase_query::tests::create_index;
+    use crate::query::{BooleanQuery, Occur, PhraseQuery, Query, TermQuery, VecDocSet};
+    use crate::schema::IndexRecordOption;
     use crate::tests::sample_with_seed;
+    use crate::{DocAddress, Term};
 
     #[test]
     fn test_exclude() {
@@ -148,4 +216,81 @@ mod tests {
             sample_skip,
         );
     }
+
+    #[test]
+    fn test_exclude_two_phase() {
+        // The approximation of the phrase "a b" matches docs 0, 1, 2 and 3,
+        // but the phrase itself only matches docs 0 and 3.
+        let index = create_index(&["a b c", "b a c", "a c b", "c a b", "c", "a b"]);
+        let text_field = index.schema().get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let term_query = TermQuery::new(
+            Term::from_field_text(text_field, "c"),
+            IndexRecordOption::WithFreqs,
+        );
+        let phrase_query = PhraseQuery::new(vec![
+            Term::from_field_text(text_field, "a"),
+            Term::from_field_text(text_field, "b"),
+        ]);
+        let term_scorer = term_query
+            .weight(&searcher, true)
+            .unwrap()
+            .scorer(segment_reader, 1.0f32)
+            .unwrap();
+        let phrase_scorer = phrase_query
+            .weight(&searcher, true)
+            .unwrap()
+            .scorer(segment_reader, 1.0f32)
+            .unwrap();
+        let mut exclude = Exclude::new(term_scorer, phrase_scorer);
+        let two_phase = exclude.two_phase().unwrap();
+        assert!(two_phase.match_cost() > 0f32);
+        let mut matching_docs = Vec::new();
+        let mut doc = two_phase.approximation().doc();
+        while doc != TERMINATED {
+            // Calling `.matches()` several times gives the same result.
+            if two_phase.matches() && two_phase.matches() {
+                matching_docs.push(doc);
+            }
+            doc = two_phase.approximation().advance();
+        }
+        assert_eq!(matching_docs, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn test_exclude_phrase_in_boolean_query() {
+        let index = create_index(&["a b c", "b a c", "a c b", "c a b", "c", "a b", "a b d"]);
+        let text_field = index.schema().get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let term_query = |text: &str| -> Box<dyn Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(text_field, text),
+                IndexRecordOption::WithFreqs,
+            ))
+        };
+        let phrase_query: Box<dyn Query> = Box::new(PhraseQuery::new(vec![
+            Term::from_field_text(text_field, "a"),
+            Term::from_field_text(text_field, "b"),
+        ]));
+        let search = |query: &BooleanQuery| {
+            let docs: Vec<DocAddress> = searcher
+                .search(query, &TEST_COLLECTOR_WITHOUT_SCORE)
+                .unwrap()
+                .docs()
+                .to_vec();
+            assert_eq!(query.count(&searcher).unwrap(), docs.len());
+            docs.iter().map(|doc| doc.doc()).collect::<Vec<_>>()
+        };
+        let exclude_phrase = BooleanQuery::from(vec![
+            (Occur::Must, term_query("c")),
+            (Occur::MustNot, phrase_query.box_clone()),
+        ]);
+        assert_eq!(search(&exclude_phrase), vec![1, 2, 4]);
+        let phrase_exclude_term = BooleanQuery::from(vec![
+            (Occur::Must, phrase_query.box_clone()),
+            (Occur::MustNot, term_query("d")),
+        ]);
+        assert_eq!(search(&phrase_exclude_term), vec![0, 3, 5]);
+    }
 }
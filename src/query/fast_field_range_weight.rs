@@ -0,0 +1,411 @@
+use crate::core::SegmentReader;
+use crate::docset::{DocSet, TERMINATED};
+use crate::fastfield::{FastFieldReader, FastValue};
+use crate::query::explanation::does_not_match;
+use crate::query::{AllScorer, ConstScorer, Explanation, Scorer, TwoPhase, Weight};
+use crate::schema::{Cardinality, Field, FieldType};
+use crate::DocId;
+use std::collections::Bound;
+
+/// `FastFieldRangeWeight` matches the documents whose fast field value
+/// is within a given range.
+///
+/// Contrary to `RangeQuery`, it does not read the term dictionary nor the
+/// postings of the field. Instead, the value of each candidate document
+/// is read from the fast field and checked against the bounds.
+/// This is typically cheaper than a `RangeQuery` when used as a filter,
+/// as an `Occur::Must` clause next to a selective query.
+///
+/// The scorer is a two-phase scorer: its approximation goes through all of
+/// the documents of the segment, and `.matches()` checks the bounds.
+/// Iterating through the scorer on its own reads the fast field value of
+/// every document. Intersected with other scorers, e.g. as an `Occur::Must`
+/// clause, it only reads the values of the documents on which the
+/// approximations of the other scorers agree.
+///
+/// Matched documents all get a constant score, the boost of the scorer.
+///
+/// The field is required to be a single-valued `u64`, `i64`, `f64` or date
+/// fast field, of the type of the bounds.
+pub struct FastFieldRangeWeight {
+    field: Field,
+    // Cardinality of the field if it is a fast field of the type of the bounds.
+    fast_field_cardinality: fn(&FieldType) -> Option<Cardinality>,
+    left_bound: Bound<u64>,
+    right_bound: Bound<u64>,
+}
+
+impl FastFieldRangeWeight {
+    /// Creates a new `FastFieldRangeWeight`.
+    ///
+    /// The bounds are `Included`, `Excluded` or `Unbounded`, which makes it
+    /// possible to express inclusive as well as exclusive ranges.
+    ///
+    /// If the field is not a fast field of the type of the bounds,
+    /// an error is returned when the scorer is created.
+    pub fn new<TFastValue: FastValue>(
+        field: Field,
+        left_bound: Bound<TFastValue>,
+        right_bound: Bound<TFastValue>,
+    ) -> FastFieldRangeWeight {
+        // The mapping of fast values to `u64` preserves their order.
+        FastFieldRangeWeight {
+            field,
+            fast_field_cardinality: TFastValue::fast_field_cardinality,
+            left_bound: map_bound_to_u64(left_bound),
+            right_bound: map_bound_to_u64(right_bound),
+        }
+    }
+
+    fn range_docset(&self, reader: &SegmentReader) -> crate::Result<FastFieldRangeDocSet> {
+        let field_entry = reader.schema().get_field_entry(self.field);
+        let field_type = field_entry.field_type();
+        let fast_field_reader_opt =
+            if (self.fast_field_cardinality)(field_type) == Some(Cardinality::SingleValue) {
+                reader.fast_fields().u64_lenient(self.field)
+            } else {
+                None
+            };
+        let fast_field_reader = fast_field_reader_opt.ok_or_else(|| {
+            crate::TantivyError::SchemaError(format!(
+                "Field {:?} of type {:?} is not a single-valued fast field of the type of the bounds.",
+                field_entry.name(),
+                field_type.value_type()
+            ))
+        })?;
+        Ok(FastFieldRangeDocSet::new(
+            AllScorer::new(reader.max_doc()),
+            fast_field_reader,
+            self.left_bound,
+            self.right_bound,
+        ))
+    }
+}
+
+fn map_bound_to_u64<TFastValue: FastValue>(bound: Bound<TFastValue>) -> Bound<u64> {
+    match bound {
+        Bound::Included(val) => Bound::Included(val.to_u64()),
+        Bound::Excluded(val) => Bound::Excluded(val.to_u64()),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+impl Weight for FastFieldRangeWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: f32) -> crate::Result<Box<dyn Scorer>> {
+        let range_docset = self.range_docset(reader)?;
+        Ok(Box::new(ConstScorer::new(range_docset, boost)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut range_docset = self.range_docset(reader)?;
+        if range_docset.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        Ok(Explanation::new("FastFieldRangeWeight", 1.0f32))
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Number of fast field values read by the current thread.
+    static NUM_FAST_FIELD_READS: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+/// `DocSet` of the documents whose fast field value is within the bounds.
+struct FastFieldRangeDocSet {
+    all_docs: AllScorer,
+    fast_field_reader: FastFieldReader<u64>,
+    left_bound: Bound<u64>,
+    right_bound: Bound<u64>,
+}
+
+impl FastFieldRangeDocSet {
+    fn new(
+        all_docs: AllScorer,
+        fast_field_reader: FastFieldReader<u64>,
+        left_bound: Bound<u64>,
+        right_bound: Bound<u64>,
+    ) -> FastFieldRangeDocSet {
+        let mut range_docset = FastFieldRangeDocSet {
+            all_docs,
+            fast_field_reader,
+            left_bound,
+            right_bound,
+        };
+        range_docset.advance_to_match();
+        range_docset
+    }
+
+    fn value_in_range(&self, val: u64) -> bool {
+        let above_left = match self.left_bound {
+            Bound::Included(left) => val >= left,
+            Bound::Excluded(left) => val > left,
+            Bound::Unbounded => true,
+        };
+        let below_right = match self.right_bound {
+            Bound::Included(right) => val <= right,
+            Bound::Excluded(right) => val < right,
+            Bound::Unbounded => true,
+        };
+        above_left && below_right
+    }
+
+    /// Advances the approximation until it is positioned on a matching document.
+    fn advance_to_match(&mut self) -> DocId {
+        let mut doc = self.all_docs.doc();
+        while doc != TERMINATED && !self.matches() {
+            doc = self.all_docs.advance();
+        }
+        doc
+    }
+}
+
+impl DocSet for FastFieldRangeDocSet {
+    fn advance(&mut self) -> DocId {
+        if self.all_docs.advance() == TERMINATED {
+            return TERMINATED;
+        }
+        self.advance_to_match()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if self.all_docs.seek(target) == TERMINATED {
+            return TERMINATED;
+        }
+        self.advance_to_match()
+    }
+
+    fn doc(&self) -> DocId {
+        self.all_docs.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.all_docs.size_hint()
+    }
+
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        Some(self)
+    }
+}
+
+impl TwoPhase for FastFieldRangeDocSet {
+    fn approximation(&mut self) -> &mut dyn DocSet {
+        &mut self.all_docs
+    }
+
+    fn matches(&mut self) -> bool {
+        let doc = self.all_docs.doc();
+        if doc == TERMINATED {
+            return false;
+        }
+        #[cfg(test)]
+        NUM_FAST_FIELD_READS.with(|num_reads| num_reads.set(num_reads.get() + 1));
+        self.value_in_range(self.fast_field_reader.get(doc))
+    }
+
+    fn match_cost(&self) -> f32 {
+        // Checking a match only requires to read a fast field value.
+        1f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FastFieldRangeWeight, NUM_FAST_FIELD_READS};
+    use crate::docset::{DocSet, TERMINATED};
+    use crate::query::boolean_query::BooleanWeight;
+    use crate::query::{CombinerKind, Occur, Query, TermQuery, Weight};
+    use crate::schema::{IndexRecordOption, Schema, FAST, INDEXED, STRING, TEXT};
+    use crate::{DocId, Index, TantivyError, Term};
+    use std::collections::Bound;
+
+    #[test]
+    fn test_fast_field_range_weight_with_term_query() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let year = schema_builder.add_i64_field("year", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(text=>"a", year=>1990i64));
+            index_writer.add_document(doc!(text=>"a b", year=>-5i64));
+            index_writer.add_document(doc!(text=>"b", year=>2000i64));
+            index_writer.add_document(doc!(text=>"a", year=>2000i64));
+            index_writer.add_document(doc!(text=>"a", year=>2010i64));
+            index_writer.add_document(doc!(text=>"a", year=>2020i64));
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let term_query = TermQuery::new(Term::from_field_text(text, "a"), IndexRecordOption::Basic);
+        let matching_docs = |left_bound: Bound<i64>, right_bound: Bound<i64>| {
+            let range_weight = FastFieldRangeWeight::new(year, left_bound, right_bound);
+            let weight = BooleanWeight::new(
+                vec![
+                    (Occur::Must, term_query.weight(&searcher, true).unwrap()),
+                    (Occur::Must, Box::new(range_weight) as Box<dyn Weight>),
+                ],
+                true,
+                None,
+                CombinerKind::default(),
+            );
+            let mut docs: Vec<DocId> = Vec::new();
+            let mut scorer = weight.scorer(segment_reader, 1.0f32).unwrap();
+            while scorer.doc() != TERMINATED {
+                docs.push(scorer.doc());
+                scorer.advance();
+            }
+            assert_eq!(weight.count(segment_reader).unwrap() as usize, docs.len());
+            docs
+        };
+        assert_eq!(
+            matching_docs(Bound::Included(2000), Bound::Included(2010)),
+            vec![3, 4]
+        );
+        assert_eq!(
+            matching_docs(Bound::Excluded(2000), Bound::Excluded(2020)),
+            vec![4]
+        );
+        assert_eq!(
+            matching_docs(Bound::Unbounded, Bound::Excluded(2000)),
+            vec![0, 1]
+        );
+        assert_eq!(
+            matching_docs(Bound::Included(-5), Bound::Unbounded),
+            vec![0, 1, 3, 4, 5]
+        );
+        assert!(matching_docs(Bound::Excluded(2020), Bound::Unbounded).is_empty());
+    }
+
+    #[test]
+    fn test_fast_field_range_weight_reads_intersected_docs() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let value = schema_builder.add_u64_field("value", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            for doc in 0u64..1_000u64 {
+                let token = if doc % 7 == 0 { "a" } else { "b" };
+                index_writer.add_document(doc!(text=>token, value=>doc % 5));
+            }
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let term_query = TermQuery::new(Term::from_field_text(text, "a"), IndexRecordOption::Basic);
+        let range_weight =
+            FastFieldRangeWeight::new(value, Bound::Included(0u64), Bound::Included(0u64));
+        let weight = BooleanWeight::new(
+            vec![
+                (Occur::Must, term_query.weight(&searcher, true).unwrap()),
+                (Occur::Must, Box::new(range_weight) as Box<dyn Weight>),
+            ],
+            true,
+            None,
+            CombinerKind::default(),
+        );
+        let num_reads_before = NUM_FAST_FIELD_READS.with(|num_reads| num_reads.get());
+        let mut docs: Vec<DocId> = Vec::new();
+        let mut scorer = weight.scorer(segment_reader, 1.0f32).unwrap();
+        while scorer.doc() != TERMINATED {
+            docs.push(scorer.doc());
+            scorer.advance();
+        }
+        let num_reads = NUM_FAST_FIELD_READS.with(|num_reads| num_reads.get()) - num_reads_before;
+        assert_eq!(docs, (0..1_000).step_by(35).collect::<Vec<DocId>>());
+        // The values are only read for the 143 documents containing "a", plus
+        // once for the first document of the range on its own.
+        assert_eq!(num_reads, 144);
+    }
+
+    #[test]
+    fn test_fast_field_range_weight_two_phase() {
+        let mut schema_builder = Schema::builder();
+        let value = schema_builder.add_u64_field("value", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            for val in &[3u64, 10u64, 7u64, 1u64, 5u64] {
+                index_writer.add_document(doc!(value=>*val));
+            }
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let range_weight =
+            FastFieldRangeWeight::new(value, Bound::Included(3u64), Bound::Excluded(7u64));
+        let mut scorer = range_weight.scorer(segment_reader, 1.0f32).unwrap();
+        assert_eq!(scorer.doc(), 0);
+        assert_eq!(scorer.seek(1), 4);
+        assert_eq!(scorer.advance(), TERMINATED);
+
+        let mut scorer = range_weight.scorer(segment_reader, 1.0f32).unwrap();
+        let two_phase = scorer.two_phase().unwrap();
+        let mut approximation_docs = Vec::new();
+        let mut matching_docs = Vec::new();
+        let mut doc = two_phase.approximation().doc();
+        while doc != TERMINATED {
+            approximation_docs.push(doc);
+            if two_phase.matches() {
+                matching_docs.push(doc);
+            }
+            doc = two_phase.approximation().advance();
+        }
+        assert_eq!(approximation_docs, vec![0, 1, 2, 3, 4]);
+        assert_eq!(matching_docs, vec![0, 4]);
+
+        assert!(range_weight.explain(segment_reader, 4).is_ok());
+        assert!(range_weight.explain(segment_reader, 2).is_err());
+    }
+
+    #[test]
+    fn test_fast_field_range_weight_field_not_fast() {
+        let mut schema_builder = Schema::builder();
+        let category = schema_builder.add_text_field("category", STRING);
+        let id = schema_builder.add_u64_field("id", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(category=>"a", id=>1u64));
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        for &field in &[category, id] {
+            let range_weight =
+                FastFieldRangeWeight::new(field, Bound::Included(0u64), Bound::Unbounded);
+            assert!(range_weight.scorer(segment_reader, 1.0f32).is_err());
+        }
+    }
+
+    #[test]
+    fn test_fast_field_range_weight_type_mismatch() {
+        let mut schema_builder = Schema::builder();
+        let year = schema_builder.add_i64_field("year", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(year=>-5i64));
+            index_writer.add_document(doc!(year=>2000i64));
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let u64_range_weight =
+            FastFieldRangeWeight::new(year, Bound::Included(0u64), Bound::Unbounded);
+        assert!(matches!(
+            u64_range_weight.scorer(segment_reader, 1.0f32),
+            Err(TantivyError::SchemaError(_))
+        ));
+        assert!(u64_range_weight.explain(segment_reader, 1).is_err());
+        let f64_range_weight =
+            FastFieldRangeWeight::new(year, Bound::Included(0f64), Bound::Unbounded);
+        assert!(f64_range_weight.scorer(segment_reader, 1.0f32).is_err());
+        let i64_range_weight =
+            FastFieldRangeWeight::new(year, Bound::Included(0i64), Bound::Unbounded);
+        let mut scorer = i64_range_weight.scorer(segment_reader, 1.0f32).unwrap();
+        assert_eq!(scorer.doc(), 1);
+        assert_eq!(scorer.advance(), TERMINATED);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::core::searcher::Searcher;
+use crate::core::SegmentReader;
+use crate::docset::{DocSet, TERMINATED};
+use crate::query::explanation::does_not_match;
+use crate::query::{Explanation, Query, Scorer, Weight};
+use crate::schema::Term;
+use crate::{DocId, Score};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+
+/// Function returning the weight given to the first query for a document.
+///
+/// The function is called with the segment local id of the document.
+pub type GateFn = Arc<dyn Fn(DocId) -> Score + Send + Sync>;
+
+/// `GateCombineQuery` blends the scores of two queries, with a weight
+/// chosen for each document by a `gate` function.
+///
+/// The query matches the union of the documents matched by `a` and `b`.
+/// The score of a document is
+///
+/// `gate(doc) * score_a + (1 - gate(doc)) * score_b`
+///
+/// where the score of a query that does not match the document is `0`.
+///
+/// The gate is typically expected to return a value between `0` and `1`,
+/// for instance the output of a model deciding which of the two retrieval
+/// signals to trust for a given document.
+/// It is called with the segment local id of the document.
+pub struct GateCombineQuery {
+    a: Box<dyn Query>,
+    b: Box<dyn Query>,
+    gate: GateFn,
+}
+
+impl GateCombineQuery {
+    /// Creates a new `GateCombineQuery`.
+    pub fn new(a: Box<dyn Query>, b: Box<dyn Query>, gate: GateFn) -> GateCombineQuery {
+        GateCombineQuery { a, b, gate }
+    }
+}
+
+impl Clone for GateCombineQuery {
+    fn clone(&self) -> Self {
+        GateCombineQuery {
+            a: self.a.box_clone(),
+            b: self.b.box_clone(),
+            gate: self.gate.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for GateCombineQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "GateCombine(a={:?}, b={:?})", self.a, self.b)
+    }
+}
+
+impl Query for GateCombineQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(GateCombineWeight {
+            weight_a: self.a.weight(searcher, scoring_enabled)?,
+            weight_b: self.b.weight(searcher, scoring_enabled)?,
+            gate: self.gate.clone(),
+        }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.a.query_terms(term_set);
+        self.b.query_terms(term_set);
+    }
+}
+
+struct GateCombineWeight {
+    weight_a: Box<dyn Weight>,
+    weight_b: Box<dyn Weight>,
+    gate: GateFn,
+}
+
+impl GateCombineWeight {
+    fn gate_combine_scorer(
+        &self,
+        reader: &SegmentReader,
+        boost: f32,
+    ) -> crate::Result<GateCombineScorer> {
+        let scorer_a = self.weight_a.scorer(reader, boost)?;
+        let scorer_b = self.weight_b.scorer(reader, boost)?;
+        Ok(GateCombineScorer::new(
+            scorer_a,
+            scorer_b,
+            self.gate.clone(),
+        ))
+    }
+}
+
+impl Weight for GateCombineWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: f32) -> crate::Result<Box<dyn Scorer>> {
+        Ok(Box::new(self.gate_combine_scorer(reader, boost)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.gate_combine_scorer(reader, 1.0f32)?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        let mut explanation = Explanation::new(
+            "GateCombineQuery. gate * score_a + (1 - gate) * score_b",
+            scorer.score(),
+        );
+        explanation.add_const("gate", (self.gate)(doc));
+        if scorer.scorer_a.doc() == doc {
+            explanation.add_detail(self.weight_a.explain(reader, doc)?);
+        }
+        if scorer.scorer_b.doc() == doc {
+            explanation.add_detail(self.weight_b.explain(reader, doc)?);
+        }
+        Ok(explanation)
+    }
+}
+
+struct GateCombineScorer {
+    scorer_a: Box<dyn Scorer>,
+    scorer_b: Box<dyn Scorer>,
+    gate: GateFn,
+}
+
+impl GateCombineScorer {
+    fn new(scorer_a: Box<dyn Scorer>, scorer_b: Box<dyn Scorer>, gate: GateFn) -> Self {
+        GateCombineScorer {
+            scorer_a,
+            scorer_b,
+            gate,
+        }
+    }
+}
+
+impl DocSet for GateCombineScorer {
+    fn advance(&mut self) -> DocId {
+        let doc = self.doc();
+        if doc == TERMINATED {
+            return TERMINATED;
+        }
+        if self.scorer_a.doc() == doc {
+            self.scorer_a.advance();
+        }
+        if self.scorer_b.doc() == doc {
+            self.scorer_b.advance();
+        }
+        self.doc()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if self.scorer_a.doc() < target {
+            self.scorer_a.seek(target);
+        }
+        if self.scorer_b.doc() < target {
+            self.scorer_b.seek(target);
+        }
+        self.doc()
+    }
+
+    fn doc(&self) -> DocId {
+        self.scorer_a.doc().min(self.scorer_b.doc())
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.scorer_a.size_hint().max(self.scorer_b.size_hint())
+    }
+}
+
+impl Scorer for GateCombineScorer {
+    fn score(&mut self) -> Score {
+        let doc = self.doc();
+        let score_a = if self.scorer_a.doc() == doc {
+            self.scorer_a.score()
+        } else {
+            0f32
+        };
+        let score_b = if self.scorer_b.doc() == doc {
+            self.scorer_b.score()
+        } else {
+            0f32
+        };
+        let gate = (self.gate)(doc);
+        gate * score_a + (1f32 - gate) * score_b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GateCombineQuery;
+    use crate::collector::tests::TEST_COLLECTOR_WITH_SCORE;
+    use crate::query::{Query, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, TEXT};
+    use crate::tests::assert_nearly_equals;
+    use crate::{DocAddress, DocId, Index, Term};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_gate_combine_query() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(text=>"a b"));
+            index_writer.add_document(doc!(text=>"a b"));
+            index_writer.add_document(doc!(text=>"a"));
+            index_writer.add_document(doc!(text=>"a"));
+            index_writer.add_document(doc!(text=>"b"));
+            index_writer.add_document(doc!(text=>"c"));
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let term_query = |word: &str| -> Box<dyn Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(text, word),
+                IndexRecordOption::WithFreqs,
+            ))
+        };
+        let score = |query: &dyn Query, doc: DocId| {
+            query
+                .explain(&searcher, DocAddress(0, doc))
+                .map(|explanation| explanation.value())
+                .unwrap_or(0f32)
+        };
+        let query_a = term_query("a");
+        let query_b = term_query("b");
+        // Picks `a` for even docs, and `b` for odd docs.
+        let gate = Arc::new(|doc: DocId| if doc % 2 == 0 { 1f32 } else { 0f32 });
+        let query = GateCombineQuery::new(query_a.box_clone(), query_b.box_clone(), gate);
+
+        let test_fruits = searcher.search(&query, &TEST_COLLECTOR_WITH_SCORE).unwrap();
+        let docs: Vec<DocId> = test_fruits.docs().iter().map(|doc| doc.doc()).collect();
+        assert_eq!(docs, vec![0, 1, 2, 3, 4]);
+        for (&doc, &doc_score) in docs.iter().zip(test_fruits.scores()) {
+            let (expected_score, other_score) = if doc % 2 == 0 {
+                (score(query_a.as_ref(), doc), score(query_b.as_ref(), doc))
+            } else {
+                (score(query_b.as_ref(), doc), score(query_a.as_ref(), doc))
+            };
+            if expected_score == 0f32 {
+                // The side picked by the gate does not match.
+                assert_eq!(doc_score, 0f32);
+                assert!(other_score > 0f32);
+            } else {
+                assert_nearly_equals(expected_score, doc_score);
+            }
+        }
+
+        let explanation = query.explain(&searcher, DocAddress(0, 1)).unwrap();
+        assert_nearly_equals(test_fruits.scores()[1], explanation.value());
+        assert!(query.explain(&searcher, DocAddress(0, 5)).is_err());
+    }
+}
@@ -2,6 +2,7 @@ use crate::docset::{DocSet, TERMINATED};
 use crate::query::term_query::TermScorer;
 use crate::query::EmptyScorer;
 use crate::query::Scorer;
+use crate::query::TwoPhaseIntersection;
 use crate::DocId;
 use crate::Score;
 
@@ -10,6 +11,10 @@ use crate::Score;
 /// The score associated to the documents is the sum of the
 /// score of the `Scorer`s given in argument.
 ///
+/// If some of the scorers are two-phase scorers (e.g. phrase scorers),
+/// a `TwoPhaseIntersection` is returned, so that their costly confirmations
+/// only happen on the documents on which all of the approximations agree.
+///
 /// For better performance, the function uses a
 /// specialized implementation if the two
 /// shortest scorers are `TermScorer`s.
@@ -20,6 +25,12 @@ pub fn intersect_scorers(mut scorers: Vec<Box<dyn Scorer>>) -> Box<dyn Scorer> {
     if scorers.len() == 1 {
         return scorers.pop().unwrap();
     }
+    if scorers
+        .iter_mut()
+        .any(|scorer| scorer.two_phase().is_some())
+    {
+        return Box::new(TwoPhaseIntersection::new(scorers));
+    }
     scorers.sort_by_key(|scorer| scorer.size_hint());
     let doc = go_to_first_doc(&mut scorers[..]);
     if doc == TERMINATED {
@@ -53,7 +64,8 @@ pub struct Intersection<TDocSet: DocSet, TOtherDocSet: DocSet = Box<dyn Scorer>>
 }
 
 fn go_to_first_doc<TDocSet: DocSet>(docsets: &mut [TDocSet]) -> DocId {
-    let mut candidate = 0;
+    // Starting from the furthest docset, as seeking backward is not supported.
+    let mut candidate = docsets.iter().map(TDocSet::doc).max().unwrap_or(0);
     'outer: loop {
         for docset in docsets.iter_mut() {
             let seek_doc = docset.seek(candidate);
@@ -68,9 +80,14 @@ fn go_to_first_doc<TDocSet: DocSet>(docsets: &mut [TDocSet]) -> DocId {
 
 impl<TDocSet: DocSet> Intersection<TDocSet, TDocSet> {
     pub(crate) fn new(mut docsets: Vec<TDocSet>) -> Intersection<TDocSet, TDocSet> {
+        docsets.sort_by_key(|docset| docset.size_hint());
+        Intersection::new_unsorted(docsets)
+    }
+
+    /// Creates an `Intersection` driven by the docsets in the given order.
+    pub(crate) fn new_unsorted(mut docsets: Vec<TDocSet>) -> Intersection<TDocSet, TDocSet> {
         let num_docsets = docsets.len();
         assert!(num_docsets >= 2);
-        docsets.sort_by_key(|docset| docset.size_hint());
         go_to_first_doc(&mut docsets);
         let left = docsets.remove(0);
         let right = docsets.remove(0);
@@ -0,0 +1,222 @@
+use crate::core::searcher::Searcher;
+use crate::core::SegmentReader;
+use crate::docset::DocSet;
+use crate::fieldnorm::FieldNormReader;
+use crate::query::explanation::does_not_match;
+use crate::query::{Explanation, Query, Scorer, TwoPhase, Weight};
+use crate::schema::{Field, Term};
+use crate::{DocId, Score};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// `InverseLengthQuery` matches the documents of a `filter` query, and
+/// scores them by the inverse of the length of a given field.
+///
+/// The score of a document is `1 / fieldnorm`, favoring the documents
+/// with a concise field. The score of the `filter` is ignored, and no
+/// term postings are read to compute the score.
+///
+/// The length is the fieldnorm of the field, as recorded at indexing time.
+/// The fieldnorm is encoded on one byte with some precision loss: it is
+/// exact for short fields, but larger lengths are rounded down to a
+/// log-scale. Documents with slightly different lengths may therefore
+/// get the same score.
+///
+/// Documents in which the field is empty are considered to have
+/// a length of `1`.
+///
+/// The field is required to be indexed.
+pub struct InverseLengthQuery {
+    filter: Box<dyn Query>,
+    field: Field,
+}
+
+impl InverseLengthQuery {
+    /// Creates a new `InverseLengthQuery`.
+    pub fn new(filter: Box<dyn Query>, field: Field) -> InverseLengthQuery {
+        InverseLengthQuery { filter, field }
+    }
+}
+
+impl Clone for InverseLengthQuery {
+    fn clone(&self) -> Self {
+        InverseLengthQuery {
+            filter: self.filter.box_clone(),
+            field: self.field,
+        }
+    }
+}
+
+impl fmt::Debug for InverseLengthQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "InverseLength(filter={:?}, field={:?})",
+            self.filter, self.field
+        )
+    }
+}
+
+impl Query for InverseLengthQuery {
+    fn weight(
+        &self,
+        searcher: &Searcher,
+        _scoring_enabled: bool,
+    ) -> crate::Result<Box<dyn Weight>> {
+        let field_entry = searcher.schema().get_field_entry(self.field);
+        if !field_entry.is_indexed() {
+            return Err(crate::TantivyError::SchemaError(format!(
+                "Applied inverse length query on field {:?}, which is not indexed",
+                field_entry.name()
+            )));
+        }
+        let filter_weight = self.filter.weight(searcher, false)?;
+        Ok(Box::new(InverseLengthWeight {
+            filter_weight,
+            field: self.field,
+        }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        self.filter.query_terms(term_set);
+    }
+}
+
+struct InverseLengthWeight {
+    filter_weight: Box<dyn Weight>,
+    field: Field,
+}
+
+impl InverseLengthWeight {
+    fn inverse_length_scorer(
+        &self,
+        reader: &SegmentReader,
+        boost: f32,
+    ) -> crate::Result<InverseLengthScorer> {
+        Ok(InverseLengthScorer {
+            filter_scorer: self.filter_weight.scorer(reader, 1.0f32)?,
+            fieldnorm_reader: reader.get_fieldnorms_reader(self.field),
+            boost,
+        })
+    }
+}
+
+impl Weight for InverseLengthWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: f32) -> crate::Result<Box<dyn Scorer>> {
+        Ok(Box::new(self.inverse_length_scorer(reader, boost)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.inverse_length_scorer(reader, 1.0f32)?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        let mut explanation = Explanation::new("InverseLengthQuery. 1 / fieldnorm", scorer.score());
+        explanation.add_const("fieldnorm", scorer.fieldnorm_reader.fieldnorm(doc) as f32);
+        Ok(explanation)
+    }
+
+    fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
+        self.filter_weight.count(reader)
+    }
+}
+
+struct InverseLengthScorer {
+    filter_scorer: Box<dyn Scorer>,
+    fieldnorm_reader: FieldNormReader,
+    boost: f32,
+}
+
+impl DocSet for InverseLengthScorer {
+    fn advance(&mut self) -> DocId {
+        self.filter_scorer.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.filter_scorer.seek(target)
+    }
+
+    fn fill_buffer(&mut self, buffer: &mut [DocId]) -> usize {
+        self.filter_scorer.fill_buffer(buffer)
+    }
+
+    fn doc(&self) -> DocId {
+        self.filter_scorer.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.filter_scorer.size_hint()
+    }
+
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        self.filter_scorer.two_phase()
+    }
+}
+
+impl Scorer for InverseLengthScorer {
+    fn score(&mut self) -> Score {
+        let fieldnorm = self.fieldnorm_reader.fieldnorm(self.doc()).max(1u32);
+        self.boost / fieldnorm as Score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InverseLengthQuery;
+    use crate::collector::tests::TEST_COLLECTOR_WITH_SCORE;
+    use crate::query::{Query, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, STORED, STRING, TEXT};
+    use crate::tests::assert_nearly_equals;
+    use crate::{DocAddress, Index, Term};
+
+    #[test]
+    fn test_inverse_length_query() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let category = schema_builder.add_text_field("category", STRING);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(category=>"a", text=>"one two three four"));
+            index_writer.add_document(doc!(category=>"b", text=>"one"));
+            index_writer.add_document(doc!(category=>"a", text=>"one two"));
+            index_writer.add_document(doc!(category=>"a", text=>"one two three four five six"));
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let filter = TermQuery::new(
+            Term::from_field_text(category, "a"),
+            IndexRecordOption::Basic,
+        );
+        let query = InverseLengthQuery::new(Box::new(filter), text);
+
+        let test_fruits = searcher.search(&query, &TEST_COLLECTOR_WITH_SCORE).unwrap();
+        assert_eq!(
+            test_fruits.docs(),
+            &[DocAddress(0, 0), DocAddress(0, 2), DocAddress(0, 3)]
+        );
+        let scores = test_fruits.scores();
+        assert_nearly_equals(0.25f32, scores[0]);
+        assert_nearly_equals(0.5f32, scores[1]);
+        assert!(scores[1] > scores[0]);
+        assert!(scores[0] > scores[2]);
+
+        let explanation = query.explain(&searcher, DocAddress(0, 2)).unwrap();
+        assert_nearly_equals(0.5f32, explanation.value());
+        assert!(query.explain(&searcher, DocAddress(0, 1)).is_err());
+        assert_eq!(query.count(&searcher).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_inverse_length_query_field_not_indexed() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let stored = schema_builder.add_text_field("stored", STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let searcher = index.reader().unwrap().searcher();
+        let filter = TermQuery::new(Term::from_field_text(text, "a"), IndexRecordOption::Basic);
+        let query = InverseLengthQuery::new(Box::new(filter), stored);
+        assert!(query.weight(&searcher, true).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use crate::docset::{DocSet, TERMINATED};
+use crate::query::score_combiner::{DoNothingCombiner, ScoreCombiner};
+use crate::query::Scorer;
+use crate::DocId;
+use crate::Score;
+use std::marker::PhantomData;
+
+/// Creates a `DocSet` that iterates through the documents matched by at least
+/// `minimum_should_match` of the given `DocSet`s.
+///
+/// The score of a document is given by `TScoreCombiner` over the scorers
+/// positioned on it.
+pub struct MinShouldMatchScorer<TScorer, TScoreCombiner = DoNothingCombiner> {
+    scorers: Vec<TScorer>,
+    minimum_should_match: usize,
+    doc: DocId,
+    _phantom: PhantomData<TScoreCombiner>,
+}
+
+impl<TScorer: Scorer, TScoreCombiner: ScoreCombiner> MinShouldMatchScorer<TScorer, TScoreCombiner> {
+    /// Creates a new `MinShouldMatchScorer`.
+    ///
+    /// # Panics
+    /// The method panics if `minimum_should_match` is 0.
+    pub fn new(
+        scorers: Vec<TScorer>,
+        minimum_should_match: usize,
+    ) -> MinShouldMatchScorer<TScorer, TScoreCombiner> {
+        assert!(minimum_should_match > 0);
+        let mut min_should_match_scorer = MinShouldMatchScorer {
+            scorers,
+            minimum_should_match,
+            doc: 0,
+            _phantom: PhantomData,
+        };
+        min_should_match_scorer.go_to_match();
+        min_should_match_scorer
+    }
+
+    /// Positions the scorer on the lowest doc matched by
+    /// at least `minimum_should_match` of the underlying scorers,
+    /// starting from the current position of the underlying scorers.
+    fn go_to_match(&mut self) -> DocId {
+        loop {
+            self.scorers.retain(|scorer| scorer.doc() != TERMINATED);
+            if self.scorers.len() < self.minimum_should_match {
+                self.doc = TERMINATED;
+                return TERMINATED;
+            }
+            let candidate = self.scorers.iter().map(DocSet::doc).min().unwrap();
+            let num_matching = self
+                .scorers
+                .iter()
+                .filter(|scorer| scorer.doc() == candidate)
+                .count();
+            if num_matching >= self.minimum_should_match {
+                self.doc = candidate;
+                return candidate;
+            }
+            for scorer in &mut self.scorers {
+                if scorer.doc() == candidate {
+                    scorer.advance();
+                }
+            }
+        }
+    }
+}
+
+impl<TScorer, TScoreCombiner> DocSet for MinShouldMatchScorer<TScorer, TScoreCombiner>
+where
+    TScorer: Scorer,
+    TScoreCombiner: ScoreCombiner,
+{
+    fn advance(&mut self) -> DocId {
+        if self.doc == TERMINATED {
+            return TERMINATED;
+        }
+        let doc = self.doc;
+        for scorer in &mut self.scorers {
+            if scorer.doc() == doc {
+                scorer.advance();
+            }
+        }
+        self.go_to_match()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if self.doc >= target {
+            return self.doc;
+        }
+        for scorer in &mut self.scorers {
+            if scorer.doc() < target {
+                scorer.seek(target);
+            }
+        }
+        self.go_to_match()
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.scorers
+            .iter()
+            .map(|scorer| scorer.size_hint())
+            .max()
+            .unwrap_or(0u32)
+    }
+}
+
+impl<TScorer, TScoreCombiner> Scorer for MinShouldMatchScorer<TScorer, TScoreCombiner>
+where
+    TScorer: Scorer,
+    TScoreCombiner: ScoreCombiner,
+{
+    fn score(&mut self) -> Score {
+        let doc = self.doc;
+        let mut score_combiner = TScoreCombiner::default();
+        for scorer in &mut self.scorers {
+            if scorer.doc() == doc {
+                score_combiner.update(scorer);
+            }
+        }
+        score_combiner.score()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MinShouldMatchScorer;
+    use crate::docset::{DocSet, TERMINATED};
+    use crate::postings::tests::test_skip_against_unoptimized;
+    use crate::query::score_combiner::{DoNothingCombiner, SumCombiner};
+    use crate::query::{ConstScorer, Scorer, VecDocSet};
+    use crate::tests::sample_with_seed;
+    use crate::DocId;
+    use std::collections::BTreeMap;
+
+    fn expected_docs(docs_list: &[Vec<DocId>], minimum_should_match: usize) -> Vec<DocId> {
+        let mut doc_counts: BTreeMap<DocId, usize> = BTreeMap::new();
+        for docs in docs_list {
+            for &doc in docs {
+                *doc_counts.entry(doc).or_default() += 1;
+            }
+        }
+        doc_counts
+            .into_iter()
+            .filter(|&(_, count)| count >= minimum_should_match)
+            .map(|(doc, _)| doc)
+            .collect()
+    }
+
+    fn make_scorer(
+        docs_list: &[Vec<DocId>],
+        minimum_should_match: usize,
+    ) -> MinShouldMatchScorer<ConstScorer<VecDocSet>, SumCombiner> {
+        MinShouldMatchScorer::new(
+            docs_list
+                .iter()
+                .cloned()
+                .map(VecDocSet::from)
+                .map(ConstScorer::from)
+                .collect(),
+            minimum_should_match,
+        )
+    }
+
+    #[test]
+    fn test_min_should_match() {
+        let docs_list = vec![
+            vec![1, 2, 3, 7, 10],
+            vec![1, 3, 9, 10],
+            vec![1, 3, 8, 9, 10],
+            vec![],
+        ];
+        for minimum_should_match in 1..=4 {
+            let mut scorer = make_scorer(&docs_list, minimum_should_match);
+            let mut docs = vec![];
+            while scorer.doc() != TERMINATED {
+                docs.push(scorer.doc());
+                assert!(scorer.score() >= minimum_should_match as f32);
+                scorer.advance();
+            }
+            assert_eq!(docs, expected_docs(&docs_list, minimum_should_match));
+        }
+    }
+
+    #[test]
+    fn test_min_should_match_skip() {
+        let docs_list = vec![
+            sample_with_seed(10_000, 0.1, 1),
+            sample_with_seed(10_000, 0.2, 2),
+            sample_with_seed(10_000, 0.05, 3),
+        ];
+        let skip_targets = sample_with_seed(10_000, 0.005, 4);
+        for minimum_should_match in 1..=3 {
+            test_skip_against_unoptimized(
+                || {
+                    Box::new(MinShouldMatchScorer::<_, DoNothingCombiner>::new(
+                        docs_list
+                            .iter()
+                            .cloned()
+                            .map(VecDocSet::from)
+                            .map(ConstScorer::from)
+                            .collect(),
+                        minimum_should_match,
+                    ))
+                },
+                skip_targets.clone(),
+            );
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::core::searcher::Searcher;
+use crate::core::SegmentReader;
+use crate::docset::DocSet;
+use crate::query::explanation::does_not_match;
+use crate::query::{
+    intersect_scorers, EmptyScorer, Exclude, Explanation, MatchAllWeight, Query, Scorer, TermQuery,
+    Weight,
+};
+use crate::schema::{IndexRecordOption, Term};
+use crate::DocId;
+use std::collections::BTreeSet;
+
+/// `MissingAnyQuery` matches the documents missing at least one of
+/// the given terms.
+///
+/// It is the complement of the intersection of the terms: a document
+/// containing all of the terms does not match, while any other document does.
+/// This is typically useful to find incomplete documents, for instance
+/// to audit the quality of some data.
+///
+/// All of the matching documents get the score `1`.
+/// A `MissingAnyQuery` without any term does not match any document.
+#[derive(Clone, Debug)]
+pub struct MissingAnyQuery {
+    terms: Vec<Term>,
+}
+
+impl MissingAnyQuery {
+    /// Creates a new `MissingAnyQuery`.
+    pub fn new(terms: Vec<Term>) -> MissingAnyQuery {
+        MissingAnyQuery { terms }
+    }
+}
+
+impl Query for MissingAnyQuery {
+    fn weight(
+        &self,
+        searcher: &Searcher,
+        _scoring_enabled: bool,
+    ) -> crate::Result<Box<dyn Weight>> {
+        let term_weights = self
+            .terms
+            .iter()
+            .map(|term| {
+                TermQuery::new(term.clone(), IndexRecordOption::Basic).weight(searcher, false)
+            })
+            .collect::<crate::Result<Vec<Box<dyn Weight>>>>()?;
+        Ok(Box::new(MissingAnyWeight { term_weights }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for term in &self.terms {
+            term_set.insert(term.clone());
+        }
+    }
+}
+
+struct MissingAnyWeight {
+    term_weights: Vec<Box<dyn Weight>>,
+}
+
+impl Weight for MissingAnyWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: f32) -> crate::Result<Box<dyn Scorer>> {
+        if self.term_weights.is_empty() {
+            return Ok(Box::new(EmptyScorer));
+        }
+        let term_scorers = self
+            .term_weights
+            .iter()
+            .map(|term_weight| term_weight.scorer(reader, 1.0f32))
+            .collect::<crate::Result<Vec<Box<dyn Scorer>>>>()?;
+        let all_terms_scorer = intersect_scorers(term_scorers);
+        let all_scorer = MatchAllWeight::default().scorer(reader, boost)?;
+        Ok(Box::new(Exclude::new(all_scorer, all_terms_scorer)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.scorer(reader, 1.0f32)?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        Ok(Explanation::new("MissingAnyQuery", scorer.score()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MissingAnyQuery;
+    use crate::collector::tests::TEST_COLLECTOR_WITH_SCORE;
+    use crate::query::Query;
+    use crate::schema::{Schema, FAST, INDEXED, TEXT};
+    use crate::{DocAddress, Index, Term};
+
+    #[test]
+    fn test_missing_any_query() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let id = schema_builder.add_u64_field("id", FAST | INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(id=>0u64, text=>"title author date"));
+            index_writer.add_document(doc!(id=>1u64, text=>"title date"));
+            index_writer.add_document(doc!(id=>2u64, text=>"author date title"));
+            index_writer.add_document(doc!(id=>3u64, text=>"author"));
+            index_writer.add_document(doc!(id=>4u64, text=>""));
+            index_writer.add_document(doc!(id=>5u64, text=>"title"));
+            index_writer.delete_term(Term::from_field_u64(id, 5u64));
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let terms = |words: &[&str]| {
+            words
+                .iter()
+                .map(|word| Term::from_field_text(text, word))
+                .collect::<Vec<Term>>()
+        };
+        let matching_docs = |query: &MissingAnyQuery| {
+            let docs: Vec<DocAddress> = searcher
+                .search(query, &TEST_COLLECTOR_WITH_SCORE)
+                .unwrap()
+                .docs()
+                .to_vec();
+            assert_eq!(query.count(&searcher).unwrap(), docs.len());
+            docs.iter().map(|doc| doc.doc()).collect::<Vec<_>>()
+        };
+        let query = MissingAnyQuery::new(terms(&["title", "author", "date"]));
+        assert_eq!(matching_docs(&query), vec![1, 3, 4]);
+        let query = MissingAnyQuery::new(terms(&["date"]));
+        assert_eq!(matching_docs(&query), vec![3, 4]);
+        let query = MissingAnyQuery::new(terms(&["title", "missing"]));
+        assert_eq!(matching_docs(&query), vec![0, 1, 2, 3, 4]);
+        let query = MissingAnyQuery::new(Vec::new());
+        assert!(matching_docs(&query).is_empty());
+
+        let query = MissingAnyQuery::new(terms(&["title", "author"]));
+        assert!(query.explain(&searcher, DocAddress(0, 1)).is_ok());
+        assert!(query.explain(&searcher, DocAddress(0, 2)).is_err());
+    }
+}
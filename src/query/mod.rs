@@ -6,19 +6,31 @@ mod bitset;
 mod bm25;
 mod boolean_query;
 mod boost_query;
+mod disjunction_max_query;
+mod dual_field_query;
 mod empty_query;
 mod exclude;
 mod explanation;
+mod fast_field_range_weight;
 mod fuzzy_query;
+mod gate_combine_query;
 mod intersection;
+mod inverse_length_query;
+mod min_should_match;
+mod missing_any_query;
 mod phrase_query;
 mod query;
 mod query_parser;
 mod range_query;
 mod regex_query;
 mod reqopt_scorer;
+mod rrf_query;
+mod same_sentence_query;
 mod scorer;
 mod term_query;
+mod twophase;
+mod twophase_intersection;
+mod twophase_union;
 mod union;
 mod weight;
 
@@ -27,33 +39,49 @@ mod vec_docset;
 
 pub(crate) mod score_combiner;
 pub use self::intersection::Intersection;
+pub use self::min_should_match::MinShouldMatchScorer;
+pub use self::twophase_intersection::TwoPhaseIntersection;
+pub use self::twophase_union::TwoPhaseUnion;
 pub use self::union::Union;
 
 #[cfg(test)]
 pub use self::vec_docset::VecDocSet;
 
-pub use self::all_query::{AllQuery, AllScorer, AllWeight};
+pub use self::all_query::{AllQuery, AllScorer, AllWeight, MatchAllWeight};
 pub use self::automaton_weight::AutomatonWeight;
 pub use self::bitset::BitSetDocSet;
 pub use self::boolean_query::BooleanQuery;
-pub use self::boost_query::BoostQuery;
+pub use self::boost_query::{BoostQuery, BoostWeight};
+pub use self::disjunction_max_query::DisjunctionMaxQuery;
+pub use self::dual_field_query::DualFieldQuery;
 pub use self::empty_query::{EmptyQuery, EmptyScorer, EmptyWeight};
 pub use self::exclude::Exclude;
 pub use self::explanation::Explanation;
+pub use self::fast_field_range_weight::FastFieldRangeWeight;
 #[cfg(test)]
 pub(crate) use self::fuzzy_query::DFAWrapper;
 pub use self::fuzzy_query::FuzzyTermQuery;
+pub use self::gate_combine_query::{GateCombineQuery, GateFn};
 pub use self::intersection::intersect_scorers;
-pub use self::phrase_query::PhraseQuery;
+pub use self::inverse_length_query::InverseLengthQuery;
+pub use self::missing_any_query::MissingAnyQuery;
+pub use self::phrase_query::{MultiPhraseGroupQuery, PhraseQuery, PhraseScorer};
 pub use self::query::Query;
 pub use self::query_parser::QueryParser;
 pub use self::query_parser::QueryParserError;
 pub use self::range_query::RangeQuery;
 pub use self::regex_query::RegexQuery;
-pub use self::reqopt_scorer::RequiredOptionalScorer;
-pub use self::scorer::ConstScorer;
+pub use self::reqopt_scorer::{OptionalScoring, RequiredOptionalScorer};
+pub use self::rrf_query::RrfQuery;
+pub use self::same_sentence_query::SameSentenceQuery;
+pub use self::score_combiner::CombinerKind;
+#[cfg(any(test, feature = "test-util"))]
+pub use self::scorer::RoundedScorer;
 pub use self::scorer::Scorer;
+pub use self::scorer::{ConstScorer, FieldValueScorer};
 pub use self::term_query::TermQuery;
+pub(crate) use self::twophase::{count_matches, match_cost};
+pub use self::twophase::{TwoPhase, TwoPhaseApproximation};
 pub use self::weight::Weight;
 pub use tantivy_query_grammar::Occur;
 
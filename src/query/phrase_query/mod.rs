@@ -1,8 +1,11 @@
+mod multi_phrase_group_query;
 mod phrase_query;
 mod phrase_scorer;
 mod phrase_weight;
 
+pub use self::multi_phrase_group_query::MultiPhraseGroupQuery;
 pub use self::phrase_query::PhraseQuery;
+pub(crate) use self::phrase_scorer::intersection;
 pub use self::phrase_scorer::PhraseScorer;
 pub use self::phrase_weight::PhraseWeight;
 
@@ -12,7 +15,9 @@ pub mod tests {
     use super::*;
     use crate::collector::tests::{TEST_COLLECTOR_WITHOUT_SCORE, TEST_COLLECTOR_WITH_SCORE};
     use crate::core::Index;
-    use crate::schema::{Schema, Term, TEXT};
+    use crate::docset::{DocSet, TERMINATED};
+    use crate::query::Query;
+    use crate::schema::{Schema, Term, INDEXED, TEXT};
     use crate::tests::assert_nearly_equals;
     use crate::DocAddress;
     use crate::DocId;
@@ -161,6 +166,67 @@ pub mod tests {
         assert_nearly_equals(scores[1], 0.46844664);
     }
 
+    #[test]
+    pub fn test_phrase_count_only_counts_matches() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let id_field = schema_builder.add_u64_field("id", INDEXED);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(text_field=>"a b", id_field=>0u64));
+            index_writer.add_document(doc!(text_field=>"b a", id_field=>1u64));
+            index_writer.add_document(doc!(text_field=>"a c b", id_field=>2u64));
+            index_writer.add_document(doc!(text_field=>"c a b", id_field=>3u64));
+            index_writer.add_document(doc!(text_field=>"a b a", id_field=>4u64));
+            assert!(index_writer.commit().is_ok());
+        }
+        let terms = vec![
+            Term::from_field_text(text_field, "a"),
+            Term::from_field_text(text_field, "b"),
+        ];
+        let phrase_query = PhraseQuery::new(terms);
+        {
+            let searcher = index.reader().unwrap().searcher();
+            let segment_reader = searcher.segment_reader(0u32);
+            let weight = phrase_query.weight(&searcher, false).unwrap();
+
+            let mut scorer = weight.scorer(segment_reader, 1.0f32).unwrap();
+            let approximation_count = scorer
+                .two_phase()
+                .unwrap()
+                .approximation()
+                .count_including_deleted();
+            assert_eq!(approximation_count, 5);
+
+            let mut scorer = weight.scorer(segment_reader, 1.0f32).unwrap();
+            let mut docs = Vec::new();
+            while scorer.doc() != TERMINATED {
+                docs.push(scorer.doc());
+                scorer.advance();
+            }
+            assert_eq!(docs, vec![0, 3, 4]);
+
+            let mut scorer = weight.scorer(segment_reader, 1.0f32).unwrap();
+            assert_eq!(scorer.count_including_deleted(), 3);
+            assert_eq!(phrase_query.count(&searcher).unwrap(), 3);
+        }
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.delete_term(Term::from_field_u64(id_field, 1u64));
+            index_writer.delete_term(Term::from_field_u64(id_field, 3u64));
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let weight = phrase_query.weight(&searcher, false).unwrap();
+        let mut scorer = weight.scorer(segment_reader, 1.0f32).unwrap();
+        let delete_bitset = segment_reader.delete_bitset().unwrap();
+        assert_eq!(scorer.count(delete_bitset), 2);
+        assert_eq!(phrase_query.count(&searcher).unwrap(), 2);
+    }
+
     #[test] // motivated by #234
     pub fn test_phrase_query_docfreq_order() {
         let mut schema_builder = Schema::builder();
@@ -0,0 +1,357 @@
+use super::{PhraseQuery, PhraseScorer, PhraseWeight};
+use crate::core::searcher::Searcher;
+use crate::core::SegmentReader;
+use crate::docset::{DocSet, TERMINATED};
+use crate::postings::Postings;
+use crate::query::explanation::does_not_match;
+use crate::query::{CombinerKind, EmptyScorer, Explanation, Query, Scorer, Weight};
+use crate::schema::{IndexRecordOption, Term};
+use crate::DocId;
+use std::collections::{BTreeSet, HashMap};
+use std::rc::Rc;
+
+/// `MultiPhraseGroupQuery` matches the documents matching any of
+/// a group of phrase queries.
+///
+/// Overlapping phrases, like `"new york"` and `"york city"`,
+/// have some terms in common. Within a segment, the postings of each
+/// of these terms are decoded only once, and shared by the
+/// scorers of all of the phrases containing the term.
+///
+/// The decoded postings, with their positions, are held in memory
+/// while the segment is being scored.
+///
+/// The scores of the phrases matching a document are combined as
+/// selected by a `CombinerKind`. By default, they are summed.
+#[derive(Clone, Debug)]
+pub struct MultiPhraseGroupQuery {
+    phrases: Vec<PhraseQuery>,
+    combiner: CombinerKind,
+}
+
+impl MultiPhraseGroupQuery {
+    /// Creates a new `MultiPhraseGroupQuery`, matching any of the given phrases.
+    pub fn new(phrases: Vec<PhraseQuery>) -> MultiPhraseGroupQuery {
+        MultiPhraseGroupQuery {
+            phrases,
+            combiner: CombinerKind::default(),
+        }
+    }
+
+    /// Sets how the scores of the matching phrases are combined.
+    pub fn with_combiner(mut self, combiner: CombinerKind) -> MultiPhraseGroupQuery {
+        self.combiner = combiner;
+        self
+    }
+}
+
+impl Query for MultiPhraseGroupQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        let phrase_weights = self
+            .phrases
+            .iter()
+            .map(|phrase| phrase.phrase_weight(searcher, scoring_enabled))
+            .collect::<crate::Result<Vec<PhraseWeight>>>()?;
+        let combiner = if scoring_enabled {
+            self.combiner
+        } else {
+            CombinerKind::None
+        };
+        Ok(Box::new(MultiPhraseGroupWeight {
+            phrase_weights,
+            combiner,
+        }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for phrase in &self.phrases {
+            phrase.query_terms(term_set);
+        }
+    }
+}
+
+struct MultiPhraseGroupWeight {
+    phrase_weights: Vec<PhraseWeight>,
+    combiner: CombinerKind,
+}
+
+impl MultiPhraseGroupWeight {
+    /// Decodes the postings of all of the distinct terms of the phrases.
+    ///
+    /// Terms absent from the segment are omitted.
+    fn decode_term_postings(&self, reader: &SegmentReader) -> HashMap<Term, Rc<DecodedPostings>> {
+        let mut term_postings: HashMap<Term, Rc<DecodedPostings>> = HashMap::new();
+        for phrase_weight in &self.phrase_weights {
+            for (_, term) in phrase_weight.phrase_terms() {
+                if term_postings.contains_key(term) {
+                    continue;
+                }
+                if let Some(postings) = reader
+                    .inverted_index(term.field())
+                    .read_postings(term, IndexRecordOption::WithFreqsAndPositions)
+                {
+                    term_postings.insert(term.clone(), Rc::new(DecodedPostings::decode(postings)));
+                }
+            }
+        }
+        term_postings
+    }
+
+    /// Returns the scorers of the phrases for which all of the terms
+    /// are present in the segment, associated with their ordinal.
+    fn phrase_scorers(
+        &self,
+        reader: &SegmentReader,
+        boost: f32,
+        term_postings: &HashMap<Term, Rc<DecodedPostings>>,
+    ) -> Vec<(usize, PhraseScorer<SharedPostings>)> {
+        let mut phrase_scorers = Vec::new();
+        'phrases: for (ord, phrase_weight) in self.phrase_weights.iter().enumerate() {
+            let mut term_postings_list = Vec::new();
+            for (offset, term) in phrase_weight.phrase_terms() {
+                if let Some(decoded) = term_postings.get(term) {
+                    term_postings_list.push((*offset, SharedPostings::new(decoded.clone())));
+                } else {
+                    continue 'phrases;
+                }
+            }
+            let phrase_scorer =
+                phrase_weight.phrase_scorer_from_postings(reader, boost, term_postings_list);
+            phrase_scorers.push((ord, phrase_scorer));
+        }
+        phrase_scorers
+    }
+}
+
+impl Weight for MultiPhraseGroupWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: f32) -> crate::Result<Box<dyn Scorer>> {
+        let term_postings = self.decode_term_postings(reader);
+        let phrase_scorers: Vec<PhraseScorer<SharedPostings>> = self
+            .phrase_scorers(reader, boost, &term_postings)
+            .into_iter()
+            .map(|(_, phrase_scorer)| phrase_scorer)
+            .collect();
+        if phrase_scorers.is_empty() {
+            return Ok(Box::new(EmptyScorer));
+        }
+        Ok(self.combiner.union(phrase_scorers))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.scorer(reader, 1.0f32)?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        let mut explanation = Explanation::new(
+            format!("MultiPhraseGroupQuery. {} ...", self.combiner.description()),
+            scorer.score(),
+        );
+        let term_postings = self.decode_term_postings(reader);
+        for (ord, mut phrase_scorer) in self.phrase_scorers(reader, 1.0f32, &term_postings) {
+            if phrase_scorer.seek(doc) == doc {
+                explanation.add_detail(self.phrase_weights[ord].explain_phrase_scorer(
+                    reader,
+                    &mut phrase_scorer,
+                    doc,
+                ));
+            }
+        }
+        Ok(explanation)
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Number of postings decoded by the current thread.
+    static NUM_DECODED_POSTINGS: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+/// Postings of a term, fully decoded in memory.
+struct DecodedPostings {
+    docs: Vec<DocId>,
+    positions: Vec<u32>,
+    // `positions[position_offsets[i]..position_offsets[i + 1]]` are the
+    // positions of the term in `docs[i]`.
+    position_offsets: Vec<usize>,
+}
+
+impl DecodedPostings {
+    fn decode<TPostings: Postings>(mut postings: TPostings) -> DecodedPostings {
+        #[cfg(test)]
+        NUM_DECODED_POSTINGS.with(|num_decoded| num_decoded.set(num_decoded.get() + 1));
+        let mut docs = Vec::new();
+        let mut positions = Vec::new();
+        let mut position_offsets = vec![0];
+        let mut doc_positions = Vec::new();
+        let mut doc = postings.doc();
+        while doc != TERMINATED {
+            docs.push(doc);
+            postings.positions(&mut doc_positions);
+            positions.extend_from_slice(&doc_positions[..]);
+            position_offsets.push(positions.len());
+            doc = postings.advance();
+        }
+        DecodedPostings {
+            docs,
+            positions,
+            position_offsets,
+        }
+    }
+}
+
+/// Cursor over some `DecodedPostings`, shared with other cursors.
+struct SharedPostings {
+    decoded: Rc<DecodedPostings>,
+    cursor: usize,
+}
+
+impl SharedPostings {
+    fn new(decoded: Rc<DecodedPostings>) -> SharedPostings {
+        SharedPostings { decoded, cursor: 0 }
+    }
+}
+
+impl DocSet for SharedPostings {
+    fn advance(&mut self) -> DocId {
+        if self.cursor < self.decoded.docs.len() {
+            self.cursor += 1;
+        }
+        self.doc()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        let remaining_docs = &self.decoded.docs[self.cursor..];
+        self.cursor += match remaining_docs.binary_search(&target) {
+            Ok(ord) | Err(ord) => ord,
+        };
+        self.doc()
+    }
+
+    fn doc(&self) -> DocId {
+        self.decoded
+            .docs
+            .get(self.cursor)
+            .cloned()
+            .unwrap_or(TERMINATED)
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.decoded.docs.len() as u32
+    }
+}
+
+impl Postings for SharedPostings {
+    fn term_freq(&self) -> u32 {
+        let offsets = &self.decoded.position_offsets;
+        (offsets[self.cursor + 1] - offsets[self.cursor]) as u32
+    }
+
+    fn positions_with_offset(&mut self, offset: u32, output: &mut Vec<u32>) {
+        let offsets = &self.decoded.position_offsets;
+        let doc_positions = &self.decoded.positions[offsets[self.cursor]..offsets[self.cursor + 1]];
+        output.clear();
+        output.extend(doc_positions.iter().map(|&position| position + offset));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::create_index;
+    use super::{MultiPhraseGroupQuery, NUM_DECODED_POSTINGS};
+    use crate::collector::tests::TEST_COLLECTOR_WITH_SCORE;
+    use crate::docset::{DocSet, TERMINATED};
+    use crate::query::{CombinerKind, PhraseQuery, Query};
+    use crate::tests::assert_nearly_equals;
+    use crate::{DocAddress, Index, Term};
+
+    fn phrase(index: &Index, words: &[&str]) -> PhraseQuery {
+        let text_field = index.schema().get_field("text").unwrap();
+        let terms = words
+            .iter()
+            .map(|word| Term::from_field_text(text_field, word))
+            .collect();
+        PhraseQuery::new(terms)
+    }
+
+    #[test]
+    fn test_multi_phrase_group_shares_postings() {
+        let index = create_index(&["new york city", "new york", "york city", "city of york"]);
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let query = MultiPhraseGroupQuery::new(vec![
+            phrase(&index, &["new", "york"]),
+            phrase(&index, &["york", "city"]),
+        ]);
+        let weight = query.weight(&searcher, true).unwrap();
+        let num_decoded_before = NUM_DECODED_POSTINGS.with(|num_decoded| num_decoded.get());
+        let mut scorer = weight.scorer(segment_reader, 1.0f32).unwrap();
+        let mut docs = Vec::new();
+        while scorer.doc() != TERMINATED {
+            docs.push(scorer.doc());
+            scorer.advance();
+        }
+        assert_eq!(docs, vec![0, 1, 2]);
+        // The postings of "york" are decoded once, and shared by both phrases.
+        let num_decoded =
+            NUM_DECODED_POSTINGS.with(|num_decoded| num_decoded.get()) - num_decoded_before;
+        assert_eq!(num_decoded, 3);
+    }
+
+    #[test]
+    fn test_multi_phrase_group_query() {
+        let index = create_index(&["new york city", "new york", "york city", "city of york"]);
+        let searcher = index.reader().unwrap().searcher();
+        let phrases = vec![
+            phrase(&index, &["new", "york"]),
+            phrase(&index, &["york", "city"]),
+        ];
+        let phrase_score = |phrase_query: &PhraseQuery, doc| {
+            phrase_query
+                .explain(&searcher, DocAddress(0, doc))
+                .map(|explanation| explanation.value())
+                .unwrap_or(0f32)
+        };
+
+        let sum_query =
+            MultiPhraseGroupQuery::new(phrases.clone()).with_combiner(CombinerKind::Sum);
+        let sum_fruits = searcher
+            .search(&sum_query, &TEST_COLLECTOR_WITH_SCORE)
+            .unwrap();
+        assert_eq!(
+            sum_fruits.docs(),
+            &[DocAddress(0, 0), DocAddress(0, 1), DocAddress(0, 2)]
+        );
+        for (doc, &score) in sum_fruits.docs().iter().zip(sum_fruits.scores()) {
+            let expected_score =
+                phrase_score(&phrases[0], doc.doc()) + phrase_score(&phrases[1], doc.doc());
+            assert_nearly_equals(expected_score, score);
+        }
+
+        let max_query =
+            MultiPhraseGroupQuery::new(phrases.clone()).with_combiner(CombinerKind::Max);
+        let max_fruits = searcher
+            .search(&max_query, &TEST_COLLECTOR_WITH_SCORE)
+            .unwrap();
+        let expected_score = phrase_score(&phrases[0], 0).max(phrase_score(&phrases[1], 0));
+        assert_nearly_equals(expected_score, max_fruits.scores()[0]);
+
+        let explanation = sum_query.explain(&searcher, DocAddress(0, 0)).unwrap();
+        assert_nearly_equals(sum_fruits.scores()[0], explanation.value());
+        assert!(explanation
+            .to_pretty_json()
+            .contains("MultiPhraseGroupQuery. Sum of ..."));
+        assert!(sum_query.explain(&searcher, DocAddress(0, 3)).is_err());
+        let explanation = max_query.explain(&searcher, DocAddress(0, 0)).unwrap();
+        assert!(explanation
+            .to_pretty_json()
+            .contains("MultiPhraseGroupQuery. Max of ..."));
+        let explanation = sum_query
+            .weight(&searcher, false)
+            .unwrap()
+            .explain(searcher.segment_reader(0u32), 0)
+            .unwrap();
+        assert!(explanation
+            .to_pretty_json()
+            .contains("MultiPhraseGroupQuery. Constant score regardless of ..."));
+    }
+}
@@ -2,7 +2,7 @@ use crate::docset::{DocSet, TERMINATED};
 use crate::fieldnorm::FieldNormReader;
 use crate::postings::Postings;
 use crate::query::bm25::BM25Weight;
-use crate::query::{Intersection, Scorer};
+use crate::query::{Intersection, Scorer, TwoPhase};
 use crate::DocId;
 use std::cmp::Ordering;
 
@@ -42,12 +42,27 @@ impl<TPostings: Postings> DocSet for PostingsWithOffset<TPostings> {
     }
 }
 
+/// Scorer of a `PhraseQuery`.
+///
+/// The scorer built by the `Weight` of a `PhraseQuery` is a
+/// `PhraseScorer<SegmentPostings>`. It can be downcast to access
+/// the positions of the matching phrases, for instance for highlighting.
 pub struct PhraseScorer<TPostings: Postings> {
     intersection_docset: Intersection<PostingsWithOffset<TPostings>, PostingsWithOffset<TPostings>>,
     num_terms: usize,
+    // Offset between the first and the last term of the phrase.
+    phrase_span: u32,
     left: Vec<u32>,
     right: Vec<u32>,
     phrase_count: u32,
+    // Document the `left` and `right` buffers were filled for.
+    matching_positions_doc: DocId,
+    // `left[..intersection_len]` are the positions of all of the terms but
+    // the last one, and `right` the positions of the last term.
+    intersection_len: usize,
+    // Once the positions of the last term are intersected,
+    // `left[..num_matching_positions]` are the positions of the phrase occurrences.
+    num_matching_positions: Option<usize>,
     fieldnorm_reader: FieldNormReader,
     similarity_weight: BM25Weight,
     score_needed: bool,
@@ -75,35 +90,11 @@ fn intersection_exists(left: &[u32], right: &[u32]) -> bool {
     false
 }
 
-fn intersection_count(left: &[u32], right: &[u32]) -> usize {
-    let mut left_i = 0;
-    let mut right_i = 0;
-    let mut count = 0;
-    while left_i < left.len() && right_i < right.len() {
-        let left_val = left[left_i];
-        let right_val = right[right_i];
-        match left_val.cmp(&right_val) {
-            Ordering::Less => {
-                left_i += 1;
-            }
-            Ordering::Equal => {
-                count += 1;
-                left_i += 1;
-                right_i += 1;
-            }
-            Ordering::Greater => {
-                right_i += 1;
-            }
-        }
-    }
-    count
-}
-
 /// Intersect twos sorted arrays `left` and `right` and outputs the
 /// resulting array in left.
 ///
 /// Returns the length of the intersection
-fn intersection(left: &mut [u32], right: &[u32]) -> usize {
+pub(crate) fn intersection(left: &mut [u32], right: &[u32]) -> usize {
     let mut left_i = 0;
     let mut right_i = 0;
     let mut count = 0;
@@ -131,6 +122,11 @@ fn intersection(left: &mut [u32], right: &[u32]) -> usize {
 }
 
 impl<TPostings: Postings> PhraseScorer<TPostings> {
+    /// Creates a new `PhraseScorer`, given the postings of the terms
+    /// with their offset within the phrase.
+    ///
+    /// If `score_needed` is false, the number of occurrences of the
+    /// phrase is not counted.
     pub fn new(
         term_postings: Vec<(usize, TPostings)>,
         similarity_weight: BM25Weight,
@@ -142,6 +138,11 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
             .map(|&(offset, _)| offset)
             .max()
             .unwrap_or(0);
+        let min_offset = term_postings
+            .iter()
+            .map(|&(offset, _)| offset)
+            .min()
+            .unwrap_or(0);
         let num_docsets = term_postings.len();
         let postings_with_offsets = term_postings
             .into_iter()
@@ -152,9 +153,13 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
         let mut scorer = PhraseScorer {
             intersection_docset: Intersection::new(postings_with_offsets),
             num_terms: num_docsets,
+            phrase_span: (max_offset - min_offset) as u32,
             left: Vec::with_capacity(100),
             right: Vec::with_capacity(100),
             phrase_count: 0u32,
+            matching_positions_doc: TERMINATED,
+            intersection_len: 0,
+            num_matching_positions: Some(0),
             similarity_weight,
             fieldnorm_reader,
             score_needed,
@@ -165,11 +170,55 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
         scorer
     }
 
+    /// Returns the number of occurrences of the phrase within the current
+    /// document.
+    ///
+    /// It is only computed if the score is needed.
     pub fn phrase_count(&self) -> u32 {
         self.phrase_count
     }
 
+    /// Returns the positions of the occurrences of the phrase within
+    /// the current document, in increasing order.
+    ///
+    /// The position of an occurrence is the position of its first term.
+    ///
+    /// The positions are computed without any allocation, from the
+    /// positions read while checking that the document matches.
+    /// If the score is not needed, that check stops at the first occurrence,
+    /// and the positions are only computed when this method is called.
+    ///
+    /// The returned slice is empty if the scorer is not positioned on
+    /// a matching document.
+    pub fn matching_positions(&mut self) -> &[u32] {
+        if self.matching_positions_doc != self.doc() {
+            return &[];
+        }
+        let num_matching_positions = match self.num_matching_positions {
+            Some(num_matching_positions) => num_matching_positions,
+            None => self.record_matching_positions(),
+        };
+        &self.left[..num_matching_positions]
+    }
+
+    /// Intersects the positions of the last term with the
+    /// `left[..intersection_len]` positions of the other terms, and records
+    /// the positions of the phrase occurrences.
+    ///
+    /// Returns the number of occurrences.
+    fn record_matching_positions(&mut self) -> usize {
+        let count = intersection(&mut self.left[..self.intersection_len], &self.right[..]);
+        // Positions are aligned on the last term of the phrase.
+        for position in &mut self.left[..count] {
+            *position -= self.phrase_span;
+        }
+        self.num_matching_positions = Some(count);
+        count
+    }
+
     fn phrase_match(&mut self) -> bool {
+        self.matching_positions_doc = self.doc();
+        self.num_matching_positions = Some(0);
         if self.score_needed {
             let count = self.compute_phrase_count();
             self.phrase_count = count;
@@ -179,7 +228,11 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
         }
     }
 
-    fn phrase_exists(&mut self) -> bool {
+    /// Reads the positions of the terms, and intersects the positions
+    /// of all of the terms but the last one into `left[..intersection_len]`.
+    ///
+    /// Returns false if that intersection is empty.
+    fn load_positions(&mut self) -> bool {
         self.intersection_docset
             .docset_mut_specialized(0)
             .positions(&mut self.left);
@@ -199,32 +252,24 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
         self.intersection_docset
             .docset_mut_specialized(self.num_terms - 1)
             .positions(&mut self.right);
-        intersection_exists(&self.left[..intersection_len], &self.right[..])
+        self.intersection_len = intersection_len;
+        self.num_matching_positions = None;
+        true
     }
 
-    fn compute_phrase_count(&mut self) -> u32 {
-        {
-            self.intersection_docset
-                .docset_mut_specialized(0)
-                .positions(&mut self.left);
-        }
-        let mut intersection_len = self.left.len();
-        for i in 1..self.num_terms - 1 {
-            {
-                self.intersection_docset
-                    .docset_mut_specialized(i)
-                    .positions(&mut self.right);
-            }
-            intersection_len = intersection(&mut self.left[..intersection_len], &self.right[..]);
-            if intersection_len == 0 {
-                return 0u32;
-            }
+    fn phrase_exists(&mut self) -> bool {
+        if !self.load_positions() {
+            return false;
         }
+        // The positions of the occurrences are computed lazily.
+        intersection_exists(&self.left[..self.intersection_len], &self.right[..])
+    }
 
-        self.intersection_docset
-            .docset_mut_specialized(self.num_terms - 1)
-            .positions(&mut self.right);
-        intersection_count(&self.left[..intersection_len], &self.right[..]) as u32
+    fn compute_phrase_count(&mut self) -> u32 {
+        if !self.load_positions() {
+            return 0u32;
+        }
+        self.record_matching_positions() as u32
     }
 }
 
@@ -253,6 +298,25 @@ impl<TPostings: Postings> DocSet for PhraseScorer<TPostings> {
     fn size_hint(&self) -> u32 {
         self.intersection_docset.size_hint()
     }
+
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        Some(self)
+    }
+}
+
+impl<TPostings: Postings> TwoPhase for PhraseScorer<TPostings> {
+    fn approximation(&mut self) -> &mut dyn DocSet {
+        &mut self.intersection_docset
+    }
+
+    fn matches(&mut self) -> bool {
+        self.phrase_match()
+    }
+
+    fn match_cost(&self) -> f32 {
+        // Checking a match requires to read and intersect the positions of each term.
+        self.num_terms as f32
+    }
 }
 
 impl<TPostings: Postings> Scorer for PhraseScorer<TPostings> {
@@ -267,7 +331,7 @@ impl<TPostings: Postings> Scorer for PhraseScorer<TPostings> {
 #[cfg(test)]
 mod tests {
 
-    use super::{intersection, intersection_count};
+    use super::{intersection, intersection_exists};
 
     fn test_intersection_sym(left: &[u32], right: &[u32], expected: &[u32]) {
         test_intersection_aux(left, right, expected);
@@ -277,7 +341,7 @@ mod tests {
     fn test_intersection_aux(left: &[u32], right: &[u32], expected: &[u32]) {
         let mut left_vec = Vec::from(left);
         let left_mut = &mut left_vec[..];
-        assert_eq!(intersection_count(left_mut, right), expected.len());
+        assert_eq!(intersection_exists(left_mut, right), !expected.is_empty());
         let count = intersection(left_mut, right);
         assert_eq!(&left_mut[..count], expected);
     }
@@ -295,7 +359,7 @@ mod tests {
 #[cfg(all(test, feature = "unstable"))]
 mod bench {
 
-    use super::{intersection, intersection_count};
+    use super::intersection;
     use test::Bencher;
 
     #[bench]
@@ -306,13 +370,4 @@ mod bench {
             intersection(&mut left, &right);
         });
     }
-
-    #[bench]
-    fn bench_intersection_count_short(b: &mut Bencher) {
-        b.iter(|| {
-            let left = [1, 5, 10, 12];
-            let right = [5, 7];
-            intersection_count(&left, &right);
-        });
-    }
 }
@@ -1,7 +1,7 @@
 use super::PhraseScorer;
 use crate::core::SegmentReader;
 use crate::fieldnorm::FieldNormReader;
-use crate::postings::SegmentPostings;
+use crate::postings::{Postings, SegmentPostings};
 use crate::query::bm25::BM25Weight;
 use crate::query::explanation::does_not_match;
 use crate::query::Scorer;
@@ -37,13 +37,46 @@ impl PhraseWeight {
         reader.get_fieldnorms_reader(field)
     }
 
+    pub(crate) fn phrase_terms(&self) -> &[(usize, Term)] {
+        &self.phrase_terms[..]
+    }
+
+    /// Builds a `PhraseScorer` given the postings of each of the
+    /// phrase terms, associated with their offset.
+    pub(crate) fn phrase_scorer_from_postings<TPostings: Postings>(
+        &self,
+        reader: &SegmentReader,
+        boost: f32,
+        term_postings_list: Vec<(usize, TPostings)>,
+    ) -> PhraseScorer<TPostings> {
+        PhraseScorer::new(
+            term_postings_list,
+            self.similarity_weight.boost_by(boost),
+            self.fieldnorm_reader(reader),
+            self.score_needed,
+        )
+    }
+
+    /// Explains the score of a `PhraseScorer` positioned on `doc`.
+    pub(crate) fn explain_phrase_scorer<TPostings: Postings>(
+        &self,
+        reader: &SegmentReader,
+        scorer: &mut PhraseScorer<TPostings>,
+        doc: DocId,
+    ) -> Explanation {
+        let fieldnorm_reader = self.fieldnorm_reader(reader);
+        let fieldnorm_id = fieldnorm_reader.fieldnorm_id(doc);
+        let phrase_count = scorer.phrase_count();
+        let mut explanation = Explanation::new("Phrase Scorer", scorer.score());
+        explanation.add_detail(self.similarity_weight.explain(fieldnorm_id, phrase_count));
+        explanation
+    }
+
     fn phrase_scorer(
         &self,
         reader: &SegmentReader,
         boost: f32,
     ) -> Result<Option<PhraseScorer<SegmentPostings>>> {
-        let similarity_weight = self.similarity_weight.boost_by(boost);
-        let fieldnorm_reader = self.fieldnorm_reader(reader);
         if reader.has_deletes() {
             let mut term_postings_list = Vec::new();
             for &(offset, ref term) in &self.phrase_terms {
@@ -56,11 +89,10 @@ impl PhraseWeight {
                     return Ok(None);
                 }
             }
-            Ok(Some(PhraseScorer::new(
+            Ok(Some(self.phrase_scorer_from_postings(
+                reader,
+                boost,
                 term_postings_list,
-                similarity_weight,
-                fieldnorm_reader,
-                self.score_needed,
             )))
         } else {
             let mut term_postings_list = Vec::new();
@@ -74,11 +106,10 @@ impl PhraseWeight {
                     return Ok(None);
                 }
             }
-            Ok(Some(PhraseScorer::new(
+            Ok(Some(self.phrase_scorer_from_postings(
+                reader,
+                boost,
                 term_postings_list,
-                similarity_weight,
-                fieldnorm_reader,
-                self.score_needed,
             )))
         }
     }
@@ -102,12 +133,7 @@ impl Weight for PhraseWeight {
         if scorer.seek(doc) != doc {
             return Err(does_not_match(doc));
         }
-        let fieldnorm_reader = self.fieldnorm_reader(reader);
-        let fieldnorm_id = fieldnorm_reader.fieldnorm_id(doc);
-        let phrase_count = scorer.phrase_count();
-        let mut explanation = Explanation::new("Phrase Scorer", scorer.score());
-        explanation.add_detail(self.similarity_weight.explain(fieldnorm_id, phrase_count));
-        Ok(explanation)
+        Ok(self.explain_phrase_scorer(reader, &mut scorer, doc))
     }
 }
 
@@ -115,7 +141,8 @@ impl Weight for PhraseWeight {
 mod tests {
     use super::super::tests::create_index;
     use crate::docset::TERMINATED;
-    use crate::query::PhraseQuery;
+    use crate::postings::SegmentPostings;
+    use crate::query::{PhraseQuery, PhraseScorer, Query, TwoPhase};
     use crate::{DocSet, Term};
 
     #[test]
@@ -140,4 +167,72 @@ mod tests {
         assert_eq!(phrase_scorer.phrase_count(), 1);
         assert_eq!(phrase_scorer.advance(), TERMINATED);
     }
+
+    #[test]
+    pub fn test_phrase_matching_positions() {
+        let index = create_index(&["a a b d a b c", "a c b", "c a b a b a b"]);
+        let text_field = index.schema().get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let phrase_query = |words: &[&str]| {
+            PhraseQuery::new(
+                words
+                    .iter()
+                    .map(|word| Term::from_field_text(text_field, word))
+                    .collect(),
+            )
+        };
+        for &scoring_enabled in &[true, false] {
+            let phrase_weight = phrase_query(&["a", "b"])
+                .phrase_weight(&searcher, scoring_enabled)
+                .unwrap();
+            let mut phrase_scorer = phrase_weight
+                .phrase_scorer(searcher.segment_reader(0u32), 1.0f32)
+                .unwrap()
+                .unwrap();
+            assert_eq!(phrase_scorer.doc(), 0);
+            assert_eq!(phrase_scorer.matching_positions(), &[1, 4]);
+            // Doc 1 contains both terms, but not the phrase.
+            assert_eq!(phrase_scorer.approximation().advance(), 1);
+            assert!(phrase_scorer.matching_positions().is_empty());
+            assert!(!phrase_scorer.matches());
+            assert!(phrase_scorer.matching_positions().is_empty());
+            assert_eq!(phrase_scorer.advance(), 2);
+            assert_eq!(phrase_scorer.matching_positions(), &[1, 3, 5]);
+            assert_eq!(phrase_scorer.advance(), TERMINATED);
+            assert!(phrase_scorer.matching_positions().is_empty());
+        }
+        let phrase_weight = phrase_query(&["a", "b", "c"])
+            .phrase_weight(&searcher, true)
+            .unwrap();
+        let mut phrase_scorer = phrase_weight
+            .phrase_scorer(searcher.segment_reader(0u32), 1.0f32)
+            .unwrap()
+            .unwrap();
+        assert_eq!(phrase_scorer.doc(), 0);
+        assert_eq!(phrase_scorer.matching_positions(), &[4]);
+
+        // The offsets of the terms do not need to start at 0.
+        let phrase_weight = PhraseQuery::new_with_offset(vec![
+            (1, Term::from_field_text(text_field, "a")),
+            (3, Term::from_field_text(text_field, "d")),
+        ])
+        .phrase_weight(&searcher, true)
+        .unwrap();
+        let mut phrase_scorer = phrase_weight
+            .phrase_scorer(searcher.segment_reader(0u32), 1.0f32)
+            .unwrap()
+            .unwrap();
+        assert_eq!(phrase_scorer.doc(), 0);
+        assert_eq!(phrase_scorer.matching_positions(), &[1]);
+
+        // Through the `Weight` of the query.
+        let weight = phrase_query(&["a", "b"]).weight(&searcher, true).unwrap();
+        let mut scorer = weight
+            .scorer(searcher.segment_reader(0u32), 1.0f32)
+            .unwrap();
+        let phrase_scorer = scorer
+            .downcast_mut::<PhraseScorer<SegmentPostings>>()
+            .unwrap();
+        assert_eq!(phrase_scorer.matching_positions(), &[1, 4]);
+    }
 }
@@ -1,10 +1,32 @@
 use crate::docset::DocSet;
 use crate::query::score_combiner::ScoreCombiner;
-use crate::query::Scorer;
+use crate::query::{Scorer, TwoPhase};
 use crate::DocId;
 use crate::Score;
 use std::marker::PhantomData;
 
+/// Defines how the score of the optional scorer of a `RequiredOptionalScorer`
+/// contributes to its score.
+#[derive(Clone, Copy, Debug)]
+pub enum OptionalScoring {
+    /// The required and optional scores are combined independently
+    /// of one another.
+    Independent,
+    /// The optional score is first multiplied by a function of the
+    /// required score.
+    ///
+    /// For instance, with `|req_score| req_score / (req_score + 1f32)`,
+    /// the optional clauses matter more for the documents strongly
+    /// matching the required clauses.
+    ScaledByRequired(fn(Score) -> Score),
+}
+
+impl Default for OptionalScoring {
+    fn default() -> Self {
+        OptionalScoring::Independent
+    }
+}
+
 /// Given a required scorer and an optional scorer
 /// matches all document from the required scorer
 /// and complements the score using the optional scorer.
@@ -12,10 +34,18 @@ use std::marker::PhantomData;
 /// This is useful for queries like `+somethingrequired somethingoptional`.
 ///
 /// Note that `somethingoptional` has no impact on the `DocSet`.
+///
+/// By default, the scores of both scorers are combined independently.
+/// See `OptionalScoring` to make the optional score depend on the required score.
+///
+/// If the required scorer is a two-phase scorer, so is the
+/// `RequiredOptionalScorer`.
 pub struct RequiredOptionalScorer<TReqScorer, TOptScorer, TScoreCombiner> {
     req_scorer: TReqScorer,
     opt_scorer: TOptScorer,
-    score_cache: Option<Score>,
+    optional_scoring: OptionalScoring,
+    // Score of the document it was computed for.
+    score_cache: Option<(DocId, Score)>,
     _phantom: PhantomData<TScoreCombiner>,
 }
 
@@ -32,10 +62,20 @@ where
         RequiredOptionalScorer {
             req_scorer,
             opt_scorer,
+            optional_scoring: OptionalScoring::default(),
             score_cache: None,
             _phantom: PhantomData,
         }
     }
+
+    /// Sets how the optional score contributes to the score.
+    pub fn with_optional_scoring(
+        mut self,
+        optional_scoring: OptionalScoring,
+    ) -> RequiredOptionalScorer<TReqScorer, TOptScorer, TScoreCombiner> {
+        self.optional_scoring = optional_scoring;
+        self
+    }
 }
 
 impl<TReqScorer, TOptScorer, TScoreCombiner> DocSet
@@ -45,7 +85,6 @@ where
     TOptScorer: DocSet,
 {
     fn advance(&mut self) -> DocId {
-        self.score_cache = None;
         self.req_scorer.advance()
     }
 
@@ -56,6 +95,11 @@ where
     fn size_hint(&self) -> u32 {
         self.req_scorer.size_hint()
     }
+
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        // The optional scorer has no impact on the `DocSet`.
+        self.req_scorer.two_phase()
+    }
 }
 
 impl<TReqScorer, TOptScorer, TScoreCombiner> Scorer
@@ -66,31 +110,44 @@ where
     TScoreCombiner: ScoreCombiner,
 {
     fn score(&mut self) -> Score {
-        if let Some(score) = self.score_cache {
-            return score;
-        }
         let doc = self.doc();
+        if let Some((cached_doc, score)) = self.score_cache {
+            if cached_doc == doc {
+                return score;
+            }
+        }
         let mut score_combiner = TScoreCombiner::default();
-        score_combiner.update(&mut self.req_scorer);
-        if self.opt_scorer.seek(doc) == doc {
-            score_combiner.update(&mut self.opt_scorer);
+        match self.optional_scoring {
+            OptionalScoring::Independent => {
+                score_combiner.update(&mut self.req_scorer);
+                if self.opt_scorer.seek(doc) == doc {
+                    score_combiner.update(&mut self.opt_scorer);
+                }
+            }
+            OptionalScoring::ScaledByRequired(scale_fn) => {
+                let req_score = self.req_scorer.score();
+                score_combiner.update_score(req_score);
+                if self.opt_scorer.seek(doc) == doc {
+                    score_combiner.update_score(self.opt_scorer.score() * scale_fn(req_score));
+                }
+            }
         }
         let score = score_combiner.score();
-        self.score_cache = Some(score);
+        self.score_cache = Some((doc, score));
         score
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::RequiredOptionalScorer;
+    use super::{OptionalScoring, RequiredOptionalScorer};
     use crate::docset::{DocSet, TERMINATED};
     use crate::postings::tests::test_skip_against_unoptimized;
     use crate::query::score_combiner::{DoNothingCombiner, SumCombiner};
     use crate::query::ConstScorer;
     use crate::query::Scorer;
     use crate::query::VecDocSet;
-    use crate::tests::sample_with_seed;
+    use crate::tests::{assert_nearly_equals, sample_with_seed};
 
     #[test]
     fn test_reqopt_scorer_empty() {
@@ -172,4 +229,31 @@ mod tests {
             skip_docs,
         );
     }
+
+    #[test]
+    fn test_reqopt_scorer_scaled_by_required() {
+        let reqopt_score = |req_score: f32, optional_scoring: OptionalScoring| {
+            let mut reqoptscorer: RequiredOptionalScorer<_, _, SumCombiner> =
+                RequiredOptionalScorer::new(
+                    ConstScorer::new(VecDocSet::from(vec![1, 3]), req_score),
+                    ConstScorer::new(VecDocSet::from(vec![1]), 1f32),
+                )
+                .with_optional_scoring(optional_scoring);
+            let score_with_opt = reqoptscorer.score();
+            reqoptscorer.advance();
+            // The optional score does not contribute when the optional scorer does not match.
+            assert_nearly_equals(req_score, reqoptscorer.score());
+            score_with_opt
+        };
+        let saturation =
+            OptionalScoring::ScaledByRequired(|req_score| req_score / (req_score + 1f32));
+        assert_nearly_equals(1.5f32, reqopt_score(1f32, saturation));
+        assert_nearly_equals(3.75f32, reqopt_score(3f32, saturation));
+        let opt_contribution = |req_score: f32| reqopt_score(req_score, saturation) - req_score;
+        assert!(opt_contribution(1f32) < opt_contribution(3f32));
+        assert!(opt_contribution(3f32) < opt_contribution(10f32));
+
+        assert_nearly_equals(2f32, reqopt_score(1f32, OptionalScoring::Independent));
+        assert_nearly_equals(4f32, reqopt_score(3f32, OptionalScoring::default()));
+    }
 }
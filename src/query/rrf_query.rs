@@ -0,0 +1,259 @@
+use crate::collector::TopDocs;
+use crate::core::{SegmentId, SegmentReader};
+use crate::docset::{DocSet, TERMINATED};
+use crate::query::explanation::does_not_match;
+use crate::query::{Explanation, Query, Scorer, Weight};
+use crate::{DocAddress, DocId, Score, Searcher, Term};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+// (sub-query ordinal, rank) pairs of a ranked document.
+type DocRanks = Vec<(usize, usize)>;
+
+/// `RrfQuery` combines several queries using Reciprocal Rank Fusion.
+///
+/// Each sub-query is first run on its own, and its top `limit` documents
+/// are ranked by decreasing score. The score of a document is then defined as
+///
+/// `sum over sub-queries of 1 / (k + rank)`
+///
+/// where `rank` starts at 1 for the best document of a sub-query.
+/// A sub-query in which the document is not ranked does not contribute.
+///
+/// Since ranks are only meaningful across the entire index, the sub-queries
+/// are executed when the `Weight` is built, and the `Weight` simply
+/// replays the fused scores segment per segment.
+///
+/// ```rust
+/// use tantivy::collector::TopDocs;
+/// use tantivy::query::{Query, RrfQuery, TermQuery};
+/// use tantivy::schema::{IndexRecordOption, Schema, TEXT};
+/// use tantivy::{doc, DocAddress, Index, Term};
+/// # fn test() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let schema = schema_builder.build();
+/// let index = Index::create_in_ram(schema);
+/// {
+///     let mut index_writer = index.writer(3_000_000)?;
+///     index_writer.add_document(doc!(title => "The Diary of Muadib"));
+///     index_writer.add_document(doc!(title => "A Dairy Cow"));
+///     index_writer.add_document(doc!(title => "The Diary of a Dairy Cow"));
+///     index_writer.commit()?;
+/// }
+/// let searcher = index.reader()?.searcher();
+/// let diary: Box<dyn Query> = Box::new(TermQuery::new(
+///     Term::from_field_text(title, "diary"),
+///     IndexRecordOption::WithFreqs,
+/// ));
+/// let cow: Box<dyn Query> = Box::new(TermQuery::new(
+///     Term::from_field_text(title, "cow"),
+///     IndexRecordOption::WithFreqs,
+/// ));
+/// let query = RrfQuery::new(vec![diary, cow], 60.0, 10);
+/// let top_docs = searcher.search(&query, &TopDocs::with_limit(1))?;
+/// assert_eq!(top_docs[0].1, DocAddress(0, 2));
+/// Ok(())
+/// # }
+/// # assert!(test().is_ok());
+/// ```
+#[derive(Debug)]
+pub struct RrfQuery {
+    queries: Vec<Box<dyn Query>>,
+    k: f32,
+    limit: usize,
+}
+
+impl Clone for RrfQuery {
+    fn clone(&self) -> Self {
+        RrfQuery {
+            queries: self.queries.iter().map(|query| query.box_clone()).collect(),
+            k: self.k,
+            limit: self.limit,
+        }
+    }
+}
+
+impl RrfQuery {
+    /// Creates a new `RrfQuery`.
+    ///
+    /// * `k` - rank constant, damping the impact of the top ranked documents.
+    ///   `60` is a common choice.
+    /// * `limit` - number of documents ranked for each sub-query.
+    ///
+    /// # Panics
+    /// The method panics if `limit` is 0.
+    pub fn new(queries: Vec<Box<dyn Query>>, k: f32, limit: usize) -> RrfQuery {
+        assert!(limit > 0, "Limit must be strictly greater than 0.");
+        RrfQuery { queries, k, limit }
+    }
+}
+
+impl Query for RrfQuery {
+    fn weight(
+        &self,
+        searcher: &Searcher,
+        _scoring_enabled: bool,
+    ) -> crate::Result<Box<dyn Weight>> {
+        let mut ranks: BTreeMap<DocAddress, DocRanks> = BTreeMap::new();
+        let top_docs = TopDocs::with_limit(self.limit);
+        for (query_ord, query) in self.queries.iter().enumerate() {
+            let ranked_docs = searcher.search(query.as_ref(), &top_docs)?;
+            for (rank_minus_one, (_score, doc_address)) in ranked_docs.into_iter().enumerate() {
+                ranks
+                    .entry(doc_address)
+                    .or_default()
+                    .push((query_ord, rank_minus_one + 1));
+            }
+        }
+        let mut ranks_per_segment: HashMap<SegmentId, Vec<(DocId, DocRanks)>> = HashMap::new();
+        for (DocAddress(segment_ord, doc), doc_ranks) in ranks {
+            let segment_id = searcher.segment_reader(segment_ord).segment_id();
+            ranks_per_segment
+                .entry(segment_id)
+                .or_default()
+                .push((doc, doc_ranks));
+        }
+        Ok(Box::new(RrfWeight {
+            k: self.k,
+            ranks_per_segment,
+        }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for query in &self.queries {
+            query.query_terms(term_set);
+        }
+    }
+}
+
+struct RrfWeight {
+    k: f32,
+    // sorted by `DocId` for each segment.
+    ranks_per_segment: HashMap<SegmentId, Vec<(DocId, DocRanks)>>,
+}
+
+impl RrfWeight {
+    fn fused_score(&self, doc_ranks: &[(usize, usize)]) -> Score {
+        doc_ranks
+            .iter()
+            .map(|&(_, rank)| 1f32 / (self.k + rank as f32))
+            .sum()
+    }
+
+    fn segment_ranks(&self, reader: &SegmentReader) -> &[(DocId, DocRanks)] {
+        self.ranks_per_segment
+            .get(&reader.segment_id())
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+}
+
+impl Weight for RrfWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: f32) -> crate::Result<Box<dyn Scorer>> {
+        let docs: Vec<(DocId, Score)> = self
+            .segment_ranks(reader)
+            .iter()
+            .map(|(doc, doc_ranks)| (*doc, boost * self.fused_score(doc_ranks)))
+            .collect();
+        Ok(Box::new(RrfScorer { docs, cursor: 0 }))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let segment_ranks = self.segment_ranks(reader);
+        let doc_ranks = segment_ranks
+            .binary_search_by_key(&doc, |(ranked_doc, _)| *ranked_doc)
+            .map(|ord| &segment_ranks[ord].1)
+            .map_err(|_| does_not_match(doc))?;
+        let mut explanation = Explanation::new(
+            format!("RrfQuery. Sum of 1 / (k + rank), with k={}", self.k),
+            self.fused_score(doc_ranks),
+        );
+        for &(query_ord, rank) in doc_ranks {
+            explanation.add_const(
+                format!("Sub-query #{}, rank {}", query_ord, rank),
+                1f32 / (self.k + rank as f32),
+            );
+        }
+        Ok(explanation)
+    }
+}
+
+struct RrfScorer {
+    docs: Vec<(DocId, Score)>,
+    cursor: usize,
+}
+
+impl DocSet for RrfScorer {
+    fn advance(&mut self) -> DocId {
+        if self.cursor < self.docs.len() {
+            self.cursor += 1;
+        }
+        self.doc()
+    }
+
+    fn doc(&self) -> DocId {
+        self.docs
+            .get(self.cursor)
+            .map(|&(doc, _)| doc)
+            .unwrap_or(TERMINATED)
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.docs.len() as u32
+    }
+}
+
+impl Scorer for RrfScorer {
+    fn score(&mut self) -> Score {
+        self.docs[self.cursor].1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RrfQuery;
+    use crate::collector::TopDocs;
+    use crate::query::{Query, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, TEXT};
+    use crate::{DocAddress, Index, Term};
+
+    #[test]
+    fn test_rrf_query_favors_docs_ranked_by_several_queries() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            index_writer.add_document(doc!(text_field=>"a a a b b b"));
+            index_writer.add_document(doc!(text_field=>"a a a a"));
+            index_writer.add_document(doc!(text_field=>"b b b b"));
+            index_writer.add_document(doc!(text_field=>"c"));
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let term_query = |text: &str| -> Box<dyn Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(text_field, text),
+                IndexRecordOption::WithFreqs,
+            ))
+        };
+        // precondition: doc 0 is never the best match of a single sub-query.
+        for text in &["a", "b"] {
+            let top_doc = searcher
+                .search(&term_query(text), &TopDocs::with_limit(1))
+                .unwrap();
+            assert_ne!(top_doc[0].1, DocAddress(0, 0));
+        }
+        let rrf_query = RrfQuery::new(vec![term_query("a"), term_query("b")], 60.0, 10);
+        let top_docs = searcher
+            .search(&rrf_query, &TopDocs::with_limit(3))
+            .unwrap();
+        assert_eq!(top_docs.len(), 3);
+        assert_eq!(top_docs[0].1, DocAddress(0, 0));
+        assert!(top_docs[0].0 > top_docs[1].0);
+        let explanation = rrf_query.explain(&searcher, DocAddress(0, 0)).unwrap();
+        assert_eq!(explanation.value(), top_docs[0].0);
+        assert_eq!(rrf_query.count(&searcher).unwrap(), 3);
+    }
+}
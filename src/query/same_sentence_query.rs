@@ -0,0 +1,377 @@
+use crate::core::searcher::Searcher;
+use crate::core::SegmentReader;
+use crate::docset::{DocSet, TERMINATED};
+use crate::fieldnorm::FieldNormReader;
+use crate::postings::{Postings, SegmentPostings};
+use crate::query::bm25::BM25Weight;
+use crate::query::explanation::does_not_match;
+use crate::query::phrase_query::intersection;
+use crate::query::{EmptyScorer, Explanation, Intersection, Query, Scorer, Weight};
+use crate::schema::{Field, IndexRecordOption, Term};
+use crate::{DocId, Score};
+use std::collections::BTreeSet;
+
+/// `SameSentenceQuery` matches documents in which all of the given terms
+/// appear within the same sentence.
+///
+/// Tantivy has no notion of sentences. Instead, sentence boundaries
+/// are expected to be indexed as a special `boundary_term` token, emitted
+/// between two sentences. For instance, with a boundary token `"eos"`, the text
+///
+/// **The cat sleeps eos The dog barks**
+///
+/// is made of two sentences. A `SameSentenceQuery` for `cat` and `sleeps` will match it,
+/// while a `SameSentenceQuery` for `cat` and `barks` will not.
+///
+/// It is the responsibility of the indexing pipeline (typically a custom tokenizer)
+/// to emit the boundary tokens. The boundary term must not clash with
+/// a regular word of the text. A document without any boundary token is
+/// considered as a single sentence.
+///
+/// The score is computed using BM25, using the number of sentences containing
+/// all of the terms in place of the term frequency.
+///
+/// Using a `SameSentenceQuery` on a field requires positions
+/// to be indexed for this field.
+#[derive(Clone, Debug)]
+pub struct SameSentenceQuery {
+    terms: Vec<Term>,
+    boundary_term: Term,
+}
+
+impl SameSentenceQuery {
+    /// Creates a new `SameSentenceQuery`.
+    ///
+    /// There must be at least two terms, and all terms, including the
+    /// `boundary_term`, must belong to the same field.
+    pub fn new(terms: Vec<Term>, boundary_term: Term) -> SameSentenceQuery {
+        assert!(
+            terms.len() > 1,
+            "A same sentence query is required to have strictly more than one term."
+        );
+        let field = boundary_term.field();
+        assert!(
+            terms.iter().all(|term| term.field() == field),
+            "All terms from a same sentence query must belong to the same field"
+        );
+        SameSentenceQuery {
+            terms,
+            boundary_term,
+        }
+    }
+
+    /// The `Field` this `SameSentenceQuery` is targeting.
+    pub fn field(&self) -> Field {
+        self.boundary_term.field()
+    }
+}
+
+impl Query for SameSentenceQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        let schema = searcher.schema();
+        let field_entry = schema.get_field_entry(self.field());
+        let has_positions = field_entry
+            .field_type()
+            .get_index_record_option()
+            .map(IndexRecordOption::has_positions)
+            .unwrap_or(false);
+        if !has_positions {
+            let field_name = field_entry.name();
+            return Err(crate::TantivyError::SchemaError(format!(
+                "Applied same sentence query on field {:?}, which does not have positions indexed",
+                field_name
+            )));
+        }
+        let similarity_weight = BM25Weight::for_terms(searcher, &self.terms);
+        Ok(Box::new(SameSentenceWeight {
+            terms: self.terms.clone(),
+            boundary_term: self.boundary_term.clone(),
+            similarity_weight,
+            score_needed: scoring_enabled,
+        }))
+    }
+
+    fn query_terms(&self, term_set: &mut BTreeSet<Term>) {
+        for term in &self.terms {
+            term_set.insert(term.clone());
+        }
+    }
+}
+
+struct SameSentenceWeight {
+    terms: Vec<Term>,
+    boundary_term: Term,
+    similarity_weight: BM25Weight,
+    score_needed: bool,
+}
+
+impl SameSentenceWeight {
+    fn fieldnorm_reader(&self, reader: &SegmentReader) -> FieldNormReader {
+        reader.get_fieldnorms_reader(self.boundary_term.field())
+    }
+
+    fn same_sentence_scorer(
+        &self,
+        reader: &SegmentReader,
+        boost: f32,
+    ) -> crate::Result<Option<SameSentenceScorer>> {
+        let inverted_index = reader.inverted_index(self.boundary_term.field());
+        let mut term_postings_list = Vec::new();
+        for term in &self.terms {
+            if let Some(postings) =
+                inverted_index.read_postings(term, IndexRecordOption::WithFreqsAndPositions)
+            {
+                term_postings_list.push(postings);
+            } else {
+                return Ok(None);
+            }
+        }
+        let boundary_postings = inverted_index.read_postings(
+            &self.boundary_term,
+            IndexRecordOption::WithFreqsAndPositions,
+        );
+        Ok(Some(SameSentenceScorer::new(
+            term_postings_list,
+            boundary_postings,
+            self.similarity_weight.boost_by(boost),
+            self.fieldnorm_reader(reader),
+            self.score_needed,
+        )))
+    }
+}
+
+impl Weight for SameSentenceWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: f32) -> crate::Result<Box<dyn Scorer>> {
+        if let Some(scorer) = self.same_sentence_scorer(reader, boost)? {
+            Ok(Box::new(scorer))
+        } else {
+            Ok(Box::new(EmptyScorer))
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self
+            .same_sentence_scorer(reader, 1.0f32)?
+            .ok_or_else(|| does_not_match(doc))?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        let fieldnorm_id = self.fieldnorm_reader(reader).fieldnorm_id(doc);
+        let sentence_count = scorer.sentence_count();
+        let mut explanation = Explanation::new("Same Sentence Scorer", scorer.score());
+        explanation.add_detail(self.similarity_weight.explain(fieldnorm_id, sentence_count));
+        Ok(explanation)
+    }
+}
+
+struct SameSentenceScorer {
+    intersection_docset: Intersection<SegmentPostings, SegmentPostings>,
+    boundary_postings: Option<SegmentPostings>,
+    num_terms: usize,
+    boundaries: Vec<u32>,
+    positions: Vec<u32>,
+    left: Vec<u32>,
+    right: Vec<u32>,
+    sentence_count: u32,
+    fieldnorm_reader: FieldNormReader,
+    similarity_weight: BM25Weight,
+    score_needed: bool,
+}
+
+/// Outputs the sorted and deduplicated ordinals of the sentences
+/// containing the given `positions`.
+///
+/// The ordinal of the sentence is the number of boundaries preceding the position.
+fn sentence_ordinals(positions: &[u32], boundaries: &[u32], output: &mut Vec<u32>) {
+    output.clear();
+    for &position in positions {
+        let sentence_ord = match boundaries.binary_search(&position) {
+            Ok(ord) | Err(ord) => ord as u32,
+        };
+        if output.last() != Some(&sentence_ord) {
+            output.push(sentence_ord);
+        }
+    }
+}
+
+impl SameSentenceScorer {
+    fn new(
+        term_postings: Vec<SegmentPostings>,
+        boundary_postings: Option<SegmentPostings>,
+        similarity_weight: BM25Weight,
+        fieldnorm_reader: FieldNormReader,
+        score_needed: bool,
+    ) -> SameSentenceScorer {
+        let num_terms = term_postings.len();
+        let mut scorer = SameSentenceScorer {
+            intersection_docset: Intersection::new(term_postings),
+            boundary_postings,
+            num_terms,
+            boundaries: Vec::new(),
+            positions: Vec::with_capacity(100),
+            left: Vec::with_capacity(100),
+            right: Vec::with_capacity(100),
+            sentence_count: 0u32,
+            fieldnorm_reader,
+            similarity_weight,
+            score_needed,
+        };
+        if scorer.doc() != TERMINATED && !scorer.sentence_match() {
+            scorer.advance();
+        }
+        scorer
+    }
+
+    /// Returns the number of sentences containing all of the terms
+    /// in the current document.
+    fn sentence_count(&self) -> u32 {
+        self.sentence_count
+    }
+
+    fn load_boundaries(&mut self) {
+        let doc = self.doc();
+        self.boundaries.clear();
+        if let Some(boundary_postings) = self.boundary_postings.as_mut() {
+            if boundary_postings.doc() < doc {
+                boundary_postings.seek(doc);
+            }
+            if boundary_postings.doc() == doc {
+                boundary_postings.positions(&mut self.boundaries);
+            }
+        }
+    }
+
+    fn sentence_match(&mut self) -> bool {
+        self.load_boundaries();
+        self.intersection_docset
+            .docset_mut_specialized(0)
+            .positions(&mut self.positions);
+        sentence_ordinals(&self.positions, &self.boundaries, &mut self.left);
+        let mut intersection_len = self.left.len();
+        for i in 1..self.num_terms {
+            self.intersection_docset
+                .docset_mut_specialized(i)
+                .positions(&mut self.positions);
+            sentence_ordinals(&self.positions, &self.boundaries, &mut self.right);
+            intersection_len = intersection(&mut self.left[..intersection_len], &self.right[..]);
+            if intersection_len == 0 {
+                break;
+            }
+        }
+        self.sentence_count = intersection_len as u32;
+        intersection_len > 0
+    }
+}
+
+impl DocSet for SameSentenceScorer {
+    fn advance(&mut self) -> DocId {
+        loop {
+            let doc = self.intersection_docset.advance();
+            if doc == TERMINATED || self.sentence_match() {
+                return doc;
+            }
+        }
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        let doc = self.intersection_docset.seek(target);
+        if doc == TERMINATED || self.sentence_match() {
+            return doc;
+        }
+        self.advance()
+    }
+
+    fn doc(&self) -> DocId {
+        self.intersection_docset.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.intersection_docset.size_hint()
+    }
+}
+
+impl Scorer for SameSentenceScorer {
+    fn score(&mut self) -> Score {
+        if !self.score_needed {
+            return 1f32;
+        }
+        let doc = self.doc();
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(doc);
+        self.similarity_weight
+            .score(fieldnorm_id, self.sentence_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sentence_ordinals, SameSentenceQuery};
+    use crate::collector::tests::TEST_COLLECTOR_WITH_SCORE;
+    use crate::query::phrase_query::tests::create_index;
+    use crate::query::Query;
+    use crate::{DocAddress, DocId, Term};
+
+    #[test]
+    fn test_sentence_ordinals() {
+        let mut output = vec![];
+        sentence_ordinals(&[0, 2, 4, 7, 9], &[3, 8], &mut output);
+        assert_eq!(output, vec![0, 1, 2]);
+        sentence_ordinals(&[4, 5], &[3, 8], &mut output);
+        assert_eq!(output, vec![1]);
+        sentence_ordinals(&[4, 5], &[], &mut output);
+        assert_eq!(output, vec![0]);
+    }
+
+    #[test]
+    fn test_same_sentence_query() {
+        let index = create_index(&[
+            "the cat sleeps eos the dog barks",
+            "the cat eos sleeps",
+            "the cat sleeps eos the cat sleeps again",
+            "sleeps the cat",
+            "the dog sleeps eos the cat barks",
+        ]);
+        let text_field = index.schema().get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let same_sentence_query = |texts: &[&str]| {
+            let terms: Vec<Term> = texts
+                .iter()
+                .map(|text| Term::from_field_text(text_field, text))
+                .collect();
+            SameSentenceQuery::new(terms, Term::from_field_text(text_field, "eos"))
+        };
+        let matching_docs = |texts: &[&str]| {
+            searcher
+                .search(&same_sentence_query(texts), &TEST_COLLECTOR_WITH_SCORE)
+                .unwrap()
+                .docs()
+                .iter()
+                .map(|doc_address| doc_address.1)
+                .collect::<Vec<DocId>>()
+        };
+        assert_eq!(matching_docs(&["cat", "sleeps"]), vec![0, 2, 3]);
+        assert_eq!(matching_docs(&["cat", "barks"]), vec![4]);
+        assert_eq!(matching_docs(&["dog", "sleeps"]), vec![4]);
+        assert_eq!(matching_docs(&["cat", "dog"]), Vec::<DocId>::new());
+        assert_eq!(matching_docs(&["cat", "sleeps", "again"]), vec![2]);
+        assert_eq!(matching_docs(&["cat", "unicorn"]), Vec::<DocId>::new());
+
+        // Two sentences containing the terms score higher than one.
+        let query = same_sentence_query(&["cat", "sleeps"]);
+        let score_0 = query.explain(&searcher, DocAddress(0, 0)).unwrap().value();
+        let score_2 = query.explain(&searcher, DocAddress(0, 2)).unwrap().value();
+        assert!(score_2 > score_0);
+        assert!(query.explain(&searcher, DocAddress(0, 1)).is_err());
+        assert_eq!(query.count(&searcher).unwrap(), 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_same_sentence_query_requires_two_terms() {
+        let index = create_index(&["a"]);
+        let text_field = index.schema().get_field("text").unwrap();
+        SameSentenceQuery::new(
+            vec![Term::from_field_text(text_field, "a")],
+            Term::from_field_text(text_field, "eos"),
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::query::Scorer;
+use crate::query::{Scorer, Union};
 use crate::Score;
 
 /// The `ScoreCombiner` trait defines how to compute
@@ -10,6 +10,10 @@ pub trait ScoreCombiner: Default + Clone + Copy + 'static {
     /// or not.
     fn update<TScorer: Scorer>(&mut self, scorer: &mut TScorer);
 
+    /// Aggregates the score combiner with a score that does not
+    /// come from a scorer as is, for instance a rescaled score.
+    fn update_score(&mut self, score: Score);
+
     /// Clears the score combiner state back to its initial state.
     fn clear(&mut self);
 
@@ -28,6 +32,8 @@ pub struct DoNothingCombiner;
 impl ScoreCombiner for DoNothingCombiner {
     fn update<TScorer: Scorer>(&mut self, _scorer: &mut TScorer) {}
 
+    fn update_score(&mut self, _score: Score) {}
+
     fn clear(&mut self) {}
 
     fn score(&self) -> Score {
@@ -43,7 +49,11 @@ pub struct SumCombiner {
 
 impl ScoreCombiner for SumCombiner {
     fn update<TScorer: Scorer>(&mut self, scorer: &mut TScorer) {
-        self.score += scorer.score();
+        self.update_score(scorer.score());
+    }
+
+    fn update_score(&mut self, score: Score) {
+        self.score += score;
     }
 
     fn clear(&mut self) {
@@ -65,7 +75,11 @@ pub struct SumWithCoordsCombiner {
 
 impl ScoreCombiner for SumWithCoordsCombiner {
     fn update<TScorer: Scorer>(&mut self, scorer: &mut TScorer) {
-        self.score += scorer.score();
+        self.update_score(scorer.score());
+    }
+
+    fn update_score(&mut self, score: Score) {
+        self.score += score;
         self.num_fields += 1;
     }
 
@@ -78,3 +92,122 @@ impl ScoreCombiner for SumWithCoordsCombiner {
         self.score
     }
 }
+
+/// Keeps the maximum score of the different scorers, and adds
+/// the sum of the other scores multiplied by a `tie_breaker` factor.
+///
+/// A `tie_breaker` of `0` gives the maximum score, while a `tie_breaker`
+/// of `1` gives the same score as the `SumCombiner`.
+///
+/// Scores may be negative. If no score was given, the score is `0`.
+#[derive(Clone, Copy)]
+pub struct MaxScoreCombiner {
+    tie_breaker: Score,
+    max: Score,
+    sum: Score,
+}
+
+impl MaxScoreCombiner {
+    /// Creates a `MaxScoreCombiner` with the given `tie_breaker` factor.
+    pub fn with_tie_breaker(tie_breaker: Score) -> MaxScoreCombiner {
+        MaxScoreCombiner {
+            tie_breaker,
+            max: Score::NEG_INFINITY,
+            sum: 0f32,
+        }
+    }
+}
+
+impl Default for MaxScoreCombiner {
+    fn default() -> Self {
+        MaxScoreCombiner::with_tie_breaker(0f32)
+    }
+}
+
+impl ScoreCombiner for MaxScoreCombiner {
+    fn update<TScorer: Scorer>(&mut self, scorer: &mut TScorer) {
+        self.update_score(scorer.score());
+    }
+
+    fn update_score(&mut self, score: Score) {
+        self.max = self.max.max(score);
+        self.sum += score;
+    }
+
+    fn clear(&mut self) {
+        self.max = Score::NEG_INFINITY;
+        self.sum = 0f32;
+    }
+
+    fn score(&self) -> Score {
+        if self.max == Score::NEG_INFINITY {
+            return 0f32;
+        }
+        self.max + self.tie_breaker * (self.sum - self.max)
+    }
+}
+
+/// Selects how a query combines the scores of its children.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CombinerKind {
+    /// Sums the scores, see `SumWithCoordsCombiner`.
+    SumWithCoords,
+    /// Sums the scores, see `SumCombiner`.
+    Sum,
+    /// Takes the maximum score, see `MaxScoreCombiner`.
+    Max,
+    /// Ignores the scores, see `DoNothingCombiner`.
+    None,
+}
+
+impl Default for CombinerKind {
+    fn default() -> Self {
+        CombinerKind::SumWithCoords
+    }
+}
+
+impl CombinerKind {
+    /// Builds the `Union` of the given scorers, combining their scores
+    /// with the selected `ScoreCombiner`.
+    pub(crate) fn union<TScorer: Scorer>(self, scorers: Vec<TScorer>) -> Box<dyn Scorer> {
+        match self {
+            CombinerKind::SumWithCoords => {
+                Box::new(Union::<TScorer, SumWithCoordsCombiner>::from(scorers))
+            }
+            CombinerKind::Sum => Box::new(Union::<TScorer, SumCombiner>::from(scorers)),
+            CombinerKind::Max => Box::new(Union::<TScorer, MaxScoreCombiner>::from(scorers)),
+            CombinerKind::None => Box::new(Union::<TScorer, DoNothingCombiner>::from(scorers)),
+        }
+    }
+
+    /// Returns how the scores are combined, as described in explanations,
+    /// e.g. `"Sum of"`.
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            CombinerKind::SumWithCoords | CombinerKind::Sum => "Sum of",
+            CombinerKind::Max => "Max of",
+            CombinerKind::None => "Constant score regardless of",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MaxScoreCombiner, ScoreCombiner};
+
+    #[test]
+    fn test_max_score_combiner_negative_scores() {
+        let mut combiner = MaxScoreCombiner::default();
+        assert_eq!(combiner.score(), 0f32);
+        combiner.update_score(-3f32);
+        combiner.update_score(-1f32);
+        assert_eq!(combiner.score(), -1f32);
+        combiner.clear();
+        assert_eq!(combiner.score(), 0f32);
+
+        let mut combiner = MaxScoreCombiner::with_tie_breaker(0.5f32);
+        combiner.update_score(-3f32);
+        combiner.update_score(-1f32);
+        assert_eq!(combiner.score(), -2.5f32);
+    }
+}
@@ -1,6 +1,9 @@
 use crate::docset::DocSet;
+use crate::fastfield::{FastFieldReader, FastValue};
+use crate::query::TwoPhase;
 use crate::DocId;
 use crate::Score;
+use crate::TERMINATED;
 use downcast_rs::impl_downcast;
 use std::ops::DerefMut;
 
@@ -12,6 +15,24 @@ pub trait Scorer: downcast_rs::Downcast + DocSet + 'static {
     ///
     /// This method will perform a bit of computation and is not cached.
     fn score(&mut self) -> Score;
+
+    /// Calls `callback` with all of the remaining `(doc, score)` pairs,
+    /// starting with the current document.
+    ///
+    /// The output is the same as calling `.score()` and `.advance()`
+    /// until the `Scorer` is exhausted, but the loop is driven by the scorer
+    /// itself. Scorers can override it to avoid the per-document cost of
+    /// these calls, for instance by going through a block of
+    /// documents at a time.
+    ///
+    /// After this call, the `Scorer` is positioned on `TERMINATED`.
+    fn for_each(&mut self, callback: &mut dyn FnMut(DocId, Score)) {
+        let mut doc = self.doc();
+        while doc != TERMINATED {
+            callback(doc, self.score());
+            doc = self.advance();
+        }
+    }
 }
 
 impl_downcast!(Scorer);
@@ -20,6 +41,10 @@ impl Scorer for Box<dyn Scorer> {
     fn score(&mut self) -> Score {
         self.deref_mut().score()
     }
+
+    fn for_each(&mut self, callback: &mut dyn FnMut(DocId, Score)) {
+        self.deref_mut().for_each(callback);
+    }
 }
 
 /// Wraps a `DocSet` and simply returns a constant `Scorer`.
@@ -66,6 +91,10 @@ impl<TDocSet: DocSet> DocSet for ConstScorer<TDocSet> {
     fn size_hint(&self) -> u32 {
         self.docset.size_hint()
     }
+
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        self.docset.two_phase()
+    }
 }
 
 impl<TDocSet: DocSet + 'static> Scorer for ConstScorer<TDocSet> {
@@ -73,3 +102,308 @@ impl<TDocSet: DocSet + 'static> Scorer for ConstScorer<TDocSet> {
         self.score
     }
 }
+
+/// Wraps a `DocSet` and scores its documents with the value of a fast field.
+///
+/// The value of the fast field for the current document is converted
+/// into a score by `value_to_score`, typically `|value| value as Score`.
+///
+/// If the fast field is missing in the segment, all of the documents
+/// get the score `default_score`.
+pub struct FieldValueScorer<TDocSet: DocSet, TFastValue: FastValue> {
+    docset: TDocSet,
+    fast_field_reader_opt: Option<FastFieldReader<TFastValue>>,
+    value_to_score: fn(TFastValue) -> Score,
+    default_score: Score,
+}
+
+impl<TDocSet: DocSet, TFastValue: FastValue> FieldValueScorer<TDocSet, TFastValue> {
+    /// Creates a new `FieldValueScorer`.
+    ///
+    /// `fast_field_reader_opt` is typically obtained from the fast field
+    /// readers of the segment, e.g. `segment_reader.fast_fields().u64(field)`.
+    pub fn new(
+        docset: TDocSet,
+        fast_field_reader_opt: Option<FastFieldReader<TFastValue>>,
+        value_to_score: fn(TFastValue) -> Score,
+        default_score: Score,
+    ) -> FieldValueScorer<TDocSet, TFastValue> {
+        FieldValueScorer {
+            docset,
+            fast_field_reader_opt,
+            value_to_score,
+            default_score,
+        }
+    }
+}
+
+impl<TDocSet: DocSet, TFastValue: FastValue> DocSet for FieldValueScorer<TDocSet, TFastValue> {
+    fn advance(&mut self) -> DocId {
+        self.docset.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.docset.seek(target)
+    }
+
+    fn fill_buffer(&mut self, buffer: &mut [DocId]) -> usize {
+        self.docset.fill_buffer(buffer)
+    }
+
+    fn doc(&self) -> DocId {
+        self.docset.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.docset.size_hint()
+    }
+
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        self.docset.two_phase()
+    }
+}
+
+impl<TDocSet, TFastValue> Scorer for FieldValueScorer<TDocSet, TFastValue>
+where
+    TDocSet: DocSet + 'static,
+    TFastValue: FastValue + 'static,
+{
+    fn score(&mut self) -> Score {
+        if let Some(fast_field_reader) = self.fast_field_reader_opt.as_ref() {
+            (self.value_to_score)(fast_field_reader.get(self.docset.doc()))
+        } else {
+            self.default_score
+        }
+    }
+}
+
+/// Wraps a `Scorer` and rounds its score to a given number of decimal places.
+///
+/// Scores may vary slightly from one platform to another. Rounding them
+/// makes it possible to write stable assertions, typically in snapshot tests.
+///
+/// This scorer is only available with the `test-util` feature.
+#[cfg(any(test, feature = "test-util"))]
+pub struct RoundedScorer<TScorer: Scorer> {
+    scorer: TScorer,
+    scale: f32,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl<TScorer: Scorer> RoundedScorer<TScorer> {
+    /// Creates a new `RoundedScorer`, rounding the scores of `scorer`
+    /// to `num_decimals` decimal places.
+    pub fn new(scorer: TScorer, num_decimals: u8) -> RoundedScorer<TScorer> {
+        RoundedScorer {
+            scorer,
+            scale: 10f32.powi(i32::from(num_decimals)),
+        }
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl<TScorer: Scorer> DocSet for RoundedScorer<TScorer> {
+    fn advance(&mut self) -> DocId {
+        self.scorer.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.scorer.seek(target)
+    }
+
+    fn fill_buffer(&mut self, buffer: &mut [DocId]) -> usize {
+        self.scorer.fill_buffer(buffer)
+    }
+
+    fn doc(&self) -> DocId {
+        self.scorer.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.scorer.size_hint()
+    }
+
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        self.scorer.two_phase()
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl<TScorer: Scorer> Scorer for RoundedScorer<TScorer> {
+    fn score(&mut self) -> Score {
+        (self.scorer.score() * self.scale).round() / self.scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConstScorer, FieldValueScorer, RoundedScorer, Scorer};
+    use crate::core::SegmentReader;
+    use crate::docset::{DocSet, TERMINATED};
+    use crate::query::boolean_query::BooleanWeight;
+    use crate::query::explanation::does_not_match;
+    use crate::query::score_combiner::SumWithCoordsCombiner;
+    use crate::query::{CombinerKind, Explanation, Occur, Union, VecDocSet, Weight};
+    use crate::schema::{Field, Schema, FAST};
+    use crate::{DocId, Index, Score};
+
+    /// Weight scoring a fixed set of documents with the value of a `u64` fast field.
+    struct FieldValueWeight {
+        docs: Vec<DocId>,
+        field: Field,
+    }
+
+    impl Weight for FieldValueWeight {
+        fn scorer(&self, reader: &SegmentReader, _boost: f32) -> crate::Result<Box<dyn Scorer>> {
+            Ok(Box::new(FieldValueScorer::new(
+                VecDocSet::from(self.docs.clone()),
+                reader.fast_fields().u64(self.field),
+                |value| value as Score,
+                0f32,
+            )))
+        }
+
+        fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+            let mut scorer = self.scorer(reader, 1.0f32)?;
+            if scorer.seek(doc) != doc {
+                return Err(does_not_match(doc));
+            }
+            Ok(Explanation::new("FieldValueWeight", scorer.score()))
+        }
+    }
+
+    fn rounded_score(score: f32, num_decimals: u8) -> f32 {
+        let scorer = ConstScorer::new(VecDocSet::from(vec![1u32]), score);
+        RoundedScorer::new(scorer, num_decimals).score()
+    }
+
+    #[test]
+    fn test_rounded_scorer() {
+        assert_eq!(rounded_score(1.234_56, 3), rounded_score(1.234_561, 3));
+        assert_eq!(rounded_score(1.234_56, 3), rounded_score(1.234_559, 3));
+        assert_eq!(rounded_score(0.1 + 0.2, 3), rounded_score(0.3, 3));
+        assert!((rounded_score(1.234_56, 3) - 1.235).abs() < 1e-6);
+        assert!((rounded_score(1.234_56, 0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rounded_scorer_docset() {
+        let scorer = ConstScorer::new(VecDocSet::from(vec![1u32, 3u32, 8u32]), 1f32);
+        let mut rounded_scorer = RoundedScorer::new(scorer, 3);
+        assert_eq!(rounded_scorer.size_hint(), 3);
+        assert_eq!(rounded_scorer.doc(), 1);
+        assert_eq!(rounded_scorer.advance(), 3);
+        assert_eq!(rounded_scorer.seek(5), 8);
+        assert_eq!(rounded_scorer.advance(), TERMINATED);
+    }
+
+    #[test]
+    fn test_field_value_scorer() {
+        let mut schema_builder = Schema::builder();
+        let popularity = schema_builder.add_u64_field("popularity", FAST);
+        let rating = schema_builder.add_f64_field("rating", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            for i in 0u64..5u64 {
+                index_writer.add_document(doc!(popularity=>i * 10, rating=>i as f64 / 2f64));
+            }
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let fast_fields = searcher.segment_reader(0u32).fast_fields();
+        let popularity_scorer = |docs: Vec<u32>| {
+            FieldValueScorer::new(
+                VecDocSet::from(docs),
+                fast_fields.u64(popularity),
+                |value| value as Score,
+                0f32,
+            )
+        };
+
+        let mut scorer = popularity_scorer(vec![1, 2, 4]);
+        assert_eq!(scorer.score(), 10f32);
+        assert_eq!(scorer.advance(), 2);
+        assert_eq!(scorer.score(), 20f32);
+        assert_eq!(scorer.seek(3), 4);
+        assert_eq!(scorer.score(), 40f32);
+        assert_eq!(scorer.advance(), TERMINATED);
+
+        let mut rating_scorer = FieldValueScorer::new(
+            VecDocSet::from(vec![3]),
+            fast_fields.f64(rating),
+            |value| value as Score,
+            0f32,
+        );
+        assert_eq!(rating_scorer.score(), 1.5f32);
+
+        // `rating` is not a u64 fast field.
+        let mut missing_field_scorer = FieldValueScorer::new(
+            VecDocSet::from(vec![3]),
+            fast_fields.u64(rating),
+            |value| value as Score,
+            7f32,
+        );
+        assert_eq!(missing_field_scorer.score(), 7f32);
+
+        let mut union = Union::<_, SumWithCoordsCombiner>::from(vec![
+            popularity_scorer(vec![0, 1, 3]),
+            popularity_scorer(vec![1, 2]),
+        ]);
+        let mut scored_docs = Vec::new();
+        while union.doc() != TERMINATED {
+            scored_docs.push((union.doc(), union.score()));
+            union.advance();
+        }
+        assert_eq!(
+            scored_docs,
+            vec![(0, 0f32), (1, 20f32), (2, 20f32), (3, 30f32)]
+        );
+    }
+
+    #[test]
+    fn test_field_value_scorer_in_boolean_should() {
+        let mut schema_builder = Schema::builder();
+        let popularity = schema_builder.add_u64_field("popularity", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            for &value in &[5u64, 12u64, 3u64, 40u64, 7u64] {
+                index_writer.add_document(doc!(popularity=>value));
+            }
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let field_value_weight = |docs: Vec<DocId>| -> Box<dyn Weight> {
+            Box::new(FieldValueWeight {
+                docs,
+                field: popularity,
+            })
+        };
+        let weight = BooleanWeight::new(
+            vec![
+                (Occur::Should, field_value_weight(vec![0, 3])),
+                (Occur::Should, field_value_weight(vec![1, 4])),
+            ],
+            true,
+            None,
+            CombinerKind::default(),
+        );
+        let mut scored_docs = Vec::new();
+        weight
+            .for_each(segment_reader, &mut |doc, score| {
+                scored_docs.push((doc, score))
+            })
+            .unwrap();
+        assert_eq!(
+            scored_docs,
+            vec![(0, 5f32), (1, 12f32), (3, 40f32), (4, 7f32)]
+        );
+        let mut scorer = weight.scorer(segment_reader, 1.0f32).unwrap();
+        assert_eq!(scorer.seek(3), 3);
+        assert_eq!(scorer.score(), 40f32);
+        assert_eq!(scorer.advance(), 4);
+        assert_eq!(scorer.score(), 7f32);
+    }
+}
@@ -10,12 +10,13 @@ pub use self::term_weight::TermWeight;
 mod tests {
 
     use crate::collector::TopDocs;
-    use crate::docset::DocSet;
+    use crate::docset::{DocSet, TERMINATED};
     use crate::query::{Query, QueryParser, Scorer, TermQuery};
     use crate::schema::{Field, IndexRecordOption, Schema, STRING, TEXT};
     use crate::tests::assert_nearly_equals;
     use crate::Index;
     use crate::Term;
+    use crate::{DocId, Score};
 
     #[test]
     pub fn test_term_query_no_freq() {
@@ -42,6 +43,49 @@ mod tests {
         assert_eq!(term_scorer.score(), 0.28768212);
     }
 
+    #[test]
+    pub fn test_term_scorer_for_each() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            // Several full blocks, followed by an incomplete one.
+            for i in 0..1_000 {
+                let text = match i % 3 {
+                    0 => "a",
+                    1 => "a a b",
+                    _ => "b c",
+                };
+                index_writer.add_document(doc!(text_field => text));
+            }
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0);
+        let term_query = TermQuery::new(
+            Term::from_field_text(text_field, "a"),
+            IndexRecordOption::WithFreqs,
+        );
+        let term_weight = term_query.weight(&searcher, true).unwrap();
+        for &seek_target in &[0, 200, 999, 1_000] {
+            let mut expected: Vec<(DocId, Score)> = Vec::new();
+            let mut term_scorer = term_weight.scorer(segment_reader, 1.0f32).unwrap();
+            term_scorer.seek(seek_target);
+            while term_scorer.doc() != TERMINATED {
+                expected.push((term_scorer.doc(), term_scorer.score()));
+                term_scorer.advance();
+            }
+            let mut output: Vec<(DocId, Score)> = Vec::new();
+            let mut term_scorer = term_weight.scorer(segment_reader, 1.0f32).unwrap();
+            term_scorer.seek(seek_target);
+            term_scorer.for_each(&mut |doc, score| output.push((doc, score)));
+            assert_eq!(output, expected);
+            assert_eq!(term_scorer.doc(), TERMINATED);
+        }
+    }
+
     #[test]
     pub fn test_term_weight() {
         let mut schema_builder = Schema::builder();
@@ -37,6 +37,37 @@ impl TermScorer {
         self.fieldnorm_reader.fieldnorm_id(self.doc())
     }
 
+    /// Returns an upper bound of the score of any of the documents
+    /// of this `TermScorer`.
+    pub fn max_score(&self) -> Score {
+        self.similarity_weight.max_score()
+    }
+
+    /// Positions the skip data on the block that may contain `target`,
+    /// without decoding it.
+    ///
+    /// Only `.seek(...)` to a target greater or equal to `target` may be
+    /// called afterwards.
+    pub(crate) fn shallow_seek(&mut self, target: DocId) {
+        self.postings.shallow_seek(target);
+    }
+
+    /// Returns the last doc of the block the skip data is positioned on.
+    pub(crate) fn last_doc_in_block(&self) -> DocId {
+        self.postings.last_doc_in_block()
+    }
+
+    /// Returns an upper bound of the score of the documents within the block
+    /// the skip data is positioned on.
+    pub(crate) fn block_max_score(&self) -> Score {
+        match self.postings.block_max_term_freq() {
+            Some(max_term_freq) => self
+                .similarity_weight
+                .max_score_for_term_freq(max_term_freq),
+            None => self.max_score(),
+        }
+    }
+
     pub fn explain(&self) -> Explanation {
         let fieldnorm_id = self.fieldnorm_id();
         let term_freq = self.term_freq();
@@ -68,4 +99,13 @@ impl Scorer for TermScorer {
         let term_freq = self.term_freq();
         self.similarity_weight.score(fieldnorm_id, term_freq)
     }
+
+    fn for_each(&mut self, callback: &mut dyn FnMut(DocId, Score)) {
+        let fieldnorm_reader = &self.fieldnorm_reader;
+        let similarity_weight = &self.similarity_weight;
+        self.postings.for_each_term_freq(|doc, term_freq| {
+            let fieldnorm_id = fieldnorm_reader.fieldnorm_id(doc);
+            callback(doc, similarity_weight.score(fieldnorm_id, term_freq));
+        });
+    }
 }
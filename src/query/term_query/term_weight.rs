@@ -92,7 +92,11 @@ impl TermWeight {
         }
     }
 
-    fn scorer_specialized(&self, reader: &SegmentReader, boost: f32) -> Result<TermScorer> {
+    pub(crate) fn scorer_specialized(
+        &self,
+        reader: &SegmentReader,
+        boost: f32,
+    ) -> Result<TermScorer> {
         let field = self.term.field();
         let inverted_index = reader.inverted_index(field);
         let fieldnorm_reader = reader.get_fieldnorms_reader(field);
@@ -0,0 +1,310 @@
+use crate::docset::{DocSet, TERMINATED};
+use crate::fastfield::DeleteBitSet;
+use crate::DocId;
+
+/// Two-phase iteration makes it possible to separate a `DocSet` into
+/// a cheap approximation and a costly confirmation.
+///
+/// The approximation iterates through a superset of the documents
+/// actually matched. For each of the approximation documents,
+/// `.matches()` confirms whether the document actually matches.
+///
+/// For instance, the approximation of a `PhraseScorer` is the intersection
+/// of its terms, while `.matches()` checks the positions of the terms.
+///
+/// Consumers can use this to defer the costly confirmation until
+/// the cheaper `DocSet`s they are combined with agree on a document.
+pub trait TwoPhase {
+    /// Returns the approximation `DocSet`.
+    ///
+    /// Advancing the approximation also moves the two-phase `DocSet`.
+    fn approximation(&mut self) -> &mut dyn DocSet;
+
+    /// Returns true iff the document the approximation is positioned
+    /// on actually matches.
+    ///
+    /// It is legal to call this method several times for the same document.
+    fn matches(&mut self) -> bool;
+
+    /// Returns an estimate of the cost of a call to `.matches()`.
+    ///
+    /// There is no unit. Costs are only meant to be compared
+    /// with one another.
+    fn match_cost(&self) -> f32;
+}
+
+/// `DocSet` going through the approximation of a two-phase `DocSet`.
+///
+/// The documents of the approximation are confirmed by calling `.matches()`.
+/// A `DocSet` which is not a two-phase `DocSet` is its own approximation,
+/// and all of its documents match.
+///
+/// This relies on the current document of a two-phase `DocSet` being the
+/// one its approximation is positioned on.
+pub struct TwoPhaseApproximation<TDocSet: DocSet> {
+    docset: TDocSet,
+}
+
+impl<TDocSet: DocSet> TwoPhaseApproximation<TDocSet> {
+    /// Creates a new `TwoPhaseApproximation`, positioned on the
+    /// current document of `docset`.
+    pub fn new(docset: TDocSet) -> TwoPhaseApproximation<TDocSet> {
+        TwoPhaseApproximation { docset }
+    }
+
+    /// Returns true iff the document the approximation is positioned
+    /// on actually matches.
+    pub fn matches(&mut self) -> bool {
+        if self.docset.doc() == TERMINATED {
+            return false;
+        }
+        self.docset
+            .two_phase()
+            .map(|two_phase| two_phase.matches())
+            .unwrap_or(true)
+    }
+
+    /// Returns the match cost of the underlying `DocSet`, or `0` if it
+    /// is not a two-phase `DocSet`.
+    pub fn match_cost(&mut self) -> f32 {
+        match_cost(&mut self.docset)
+    }
+
+    /// Returns the underlying `DocSet`.
+    ///
+    /// It is only guaranteed to be positioned on a matching document
+    /// if `.matches()` returned true for its current document.
+    pub fn docset_mut(&mut self) -> &mut TDocSet {
+        &mut self.docset
+    }
+
+    fn approximation(&mut self) -> &mut dyn DocSet {
+        if self.docset.two_phase().is_some() {
+            return self.docset.two_phase().unwrap().approximation();
+        }
+        &mut self.docset
+    }
+}
+
+impl<TDocSet: DocSet> DocSet for TwoPhaseApproximation<TDocSet> {
+    fn advance(&mut self) -> DocId {
+        self.approximation().advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.approximation().seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.docset.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.docset.size_hint()
+    }
+}
+
+/// Returns the match cost of a `DocSet`, or `0` if it is not
+/// a two-phase `DocSet`.
+pub(crate) fn match_cost<TDocSet: DocSet + ?Sized>(docset: &mut TDocSet) -> f32 {
+    docset
+        .two_phase()
+        .map(|two_phase| two_phase.match_cost())
+        .unwrap_or(0f32)
+}
+
+/// Counts the documents matched by a two-phase `DocSet`, from
+/// its current document, by iterating through its approximation.
+///
+/// Deleted documents are not counted, and `.matches()` is not called for them.
+pub(crate) fn count_matches(
+    two_phase: &mut dyn TwoPhase,
+    delete_bitset_opt: Option<&DeleteBitSet>,
+) -> u32 {
+    let mut count = 0u32;
+    let mut doc = two_phase.approximation().doc();
+    while doc != TERMINATED {
+        let is_deleted = delete_bitset_opt
+            .map(|delete_bitset| delete_bitset.is_deleted(doc))
+            .unwrap_or(false);
+        if !is_deleted && two_phase.matches() {
+            count += 1u32;
+        }
+        doc = two_phase.approximation().advance();
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TwoPhase, TwoPhaseApproximation};
+    use crate::directory::ReadOnlySource;
+    use crate::docset::{DocSet, TERMINATED};
+    use crate::fastfield::DeleteBitSet;
+    use crate::query::phrase_query::tests::create_index;
+    use crate::query::score_combiner::SumWithCoordsCombiner;
+    use crate::query::{
+        BooleanQuery, Occur, PhraseQuery, Query, RequiredOptionalScorer, Scorer, TermQuery,
+        VecDocSet,
+    };
+    use crate::schema::IndexRecordOption;
+    use crate::tests::assert_nearly_equals;
+    use crate::{DocAddress, DocId, Index, Term};
+
+    fn approximation_docs<TDocSet: DocSet>(
+        approximation: &mut TwoPhaseApproximation<TDocSet>,
+    ) -> Vec<(DocId, bool)> {
+        let mut docs = Vec::new();
+        let mut doc = approximation.doc();
+        while doc != TERMINATED {
+            docs.push((doc, approximation.matches()));
+            doc = approximation.advance();
+        }
+        docs
+    }
+
+    /// Two-phase `DocSet` whose iteration goes through all of the
+    /// documents of its approximation.
+    struct UnconfirmedDocSet {
+        approximation: VecDocSet,
+        matching_docs: Vec<DocId>,
+        num_confirmations: usize,
+    }
+
+    impl DocSet for UnconfirmedDocSet {
+        fn advance(&mut self) -> DocId {
+            self.approximation.advance()
+        }
+
+        fn doc(&self) -> DocId {
+            self.approximation.doc()
+        }
+
+        fn size_hint(&self) -> u32 {
+            self.approximation.size_hint()
+        }
+
+        fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+            Some(self)
+        }
+    }
+
+    impl TwoPhase for UnconfirmedDocSet {
+        fn approximation(&mut self) -> &mut dyn DocSet {
+            &mut self.approximation
+        }
+
+        fn matches(&mut self) -> bool {
+            self.num_confirmations += 1;
+            self.matching_docs.contains(&self.approximation.doc())
+        }
+
+        fn match_cost(&self) -> f32 {
+            1f32
+        }
+    }
+
+    fn phrase_query(index: &Index) -> Box<dyn Query> {
+        let text_field = index.schema().get_field("text").unwrap();
+        Box::new(PhraseQuery::new(vec![
+            Term::from_field_text(text_field, "a"),
+            Term::from_field_text(text_field, "b"),
+        ]))
+    }
+
+    #[test]
+    fn test_two_phase_approximation() {
+        let index = create_index(&["a b", "b a", "a c b", "a b c"]);
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let weight = phrase_query(&index).weight(&searcher, true).unwrap();
+
+        let mut approximation =
+            TwoPhaseApproximation::new(weight.scorer(segment_reader, 1.0f32).unwrap());
+        assert_eq!(approximation.match_cost(), 2f32);
+        assert_eq!(
+            approximation_docs(&mut approximation),
+            vec![(0, true), (1, false), (2, false), (3, true)]
+        );
+        assert!(!approximation.matches());
+
+        let mut approximation =
+            TwoPhaseApproximation::new(weight.scorer(segment_reader, 1.0f32).unwrap());
+        assert_eq!(approximation.seek(1), 1);
+        assert!(!approximation.matches());
+        assert_eq!(approximation.seek(3), 3);
+        assert!(approximation.matches());
+        let explanation = weight.explain(segment_reader, 3).unwrap();
+        assert_nearly_equals(explanation.value(), approximation.docset_mut().score());
+        assert_eq!(approximation.advance(), TERMINATED);
+    }
+
+    #[test]
+    fn test_count_two_phase_docset() {
+        let docset = || UnconfirmedDocSet {
+            approximation: VecDocSet::from(vec![1, 2, 4, 5, 8]),
+            matching_docs: vec![2, 5, 8],
+            num_confirmations: 0,
+        };
+        let mut unconfirmed_docset = docset();
+        assert_eq!(unconfirmed_docset.count_including_deleted(), 3);
+        assert_eq!(unconfirmed_docset.num_confirmations, 5);
+        // Documents 2 and 5 are deleted.
+        let delete_bitset = DeleteBitSet::open(ReadOnlySource::from(vec![0b0010_0100u8, 0u8]));
+        let mut unconfirmed_docset = docset();
+        assert_eq!(unconfirmed_docset.count(&delete_bitset), 1);
+        assert_eq!(unconfirmed_docset.num_confirmations, 3);
+    }
+
+    #[test]
+    fn test_two_phase_approximation_not_two_phase() {
+        let mut approximation = TwoPhaseApproximation::new(VecDocSet::from(vec![1, 4]));
+        assert_eq!(approximation.match_cost(), 0f32);
+        assert_eq!(
+            approximation_docs(&mut approximation),
+            vec![(1, true), (4, true)]
+        );
+    }
+
+    #[test]
+    fn test_two_phase_approximation_reqopt() {
+        let index = create_index(&["a b", "b a", "a c b", "a b c"]);
+        let text_field = index.schema().get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let query = BooleanQuery::from(vec![
+            (Occur::Must, phrase_query(&index)),
+            (
+                Occur::Should,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(text_field, "c"),
+                    IndexRecordOption::WithFreqs,
+                )) as Box<dyn Query>,
+            ),
+        ]);
+        let weight = query.weight(&searcher, true).unwrap();
+        let scorer = weight.scorer(segment_reader, 1.0f32).unwrap();
+        assert!(scorer.is::<RequiredOptionalScorer<
+            Box<dyn Scorer>,
+            Box<dyn Scorer>,
+            SumWithCoordsCombiner,
+        >>());
+        let mut approximation = TwoPhaseApproximation::new(scorer);
+        let first_score = approximation.docset_mut().score();
+        assert_eq!(
+            approximation_docs(&mut approximation),
+            vec![(0, true), (1, false), (2, false), (3, true)]
+        );
+        let mut approximation =
+            TwoPhaseApproximation::new(weight.scorer(segment_reader, 1.0f32).unwrap());
+        assert_eq!(approximation.seek(2), 2);
+        assert!(!approximation.matches());
+        assert_eq!(approximation.advance(), 3);
+        assert!(approximation.matches());
+        // The score is recomputed for the document the approximation moved to.
+        let score = approximation.docset_mut().score();
+        assert!(score > first_score);
+        let explanation = query.explain(&searcher, DocAddress(0u32, 3u32)).unwrap();
+        assert_nearly_equals(explanation.value(), score);
+    }
+}
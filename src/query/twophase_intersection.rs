@@ -0,0 +1,191 @@
+use crate::docset::{DocSet, TERMINATED};
+use crate::query::{Intersection, Scorer, TwoPhase, TwoPhaseApproximation};
+use crate::DocId;
+use crate::Score;
+
+/// Intersection of `Scorer`s, some of which are two-phase scorers.
+///
+/// Unlike `Intersection`, the `TwoPhaseIntersection` does not confirm the
+/// documents of its two-phase scorers eagerly. Its approximation is the
+/// intersection of the approximations of the scorers, and `.matches()`
+/// checks the scorers by ascending `match_cost()`, stopping at the first
+/// one that does not actually match the document.
+/// The costly confirmations therefore only happen on the documents on
+/// which all of the approximations and all of the cheaper scorers agree.
+///
+/// The `match_cost()` of the intersection is the sum of the
+/// `match_cost()` of its scorers.
+///
+/// The score associated to the documents is the sum of the
+/// score of the scorers.
+pub struct TwoPhaseIntersection<TScorer: Scorer> {
+    approximation: Intersection<TwoPhaseApproximation<TScorer>, TwoPhaseApproximation<TScorer>>,
+    // Ordinals of the two-phase scorers, by ascending match cost.
+    two_phase_ords: Vec<usize>,
+    num_scorers: usize,
+    match_cost: f32,
+}
+
+impl<TScorer: Scorer> TwoPhaseIntersection<TScorer> {
+    /// Creates a new `TwoPhaseIntersection`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if less than two scorers are given.
+    pub fn new(scorers: Vec<TScorer>) -> TwoPhaseIntersection<TScorer> {
+        let num_scorers = scorers.len();
+        let mut approximation = Intersection::new(
+            scorers
+                .into_iter()
+                .map(TwoPhaseApproximation::new)
+                .collect(),
+        );
+        let mut ords_with_cost: Vec<(f32, usize)> = (0..num_scorers)
+            .filter_map(|ord| {
+                let scorer = approximation.docset_mut_specialized(ord);
+                if scorer.docset_mut().two_phase().is_some() {
+                    Some((scorer.match_cost(), ord))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        ords_with_cost.sort_by(|(left_cost, _), (right_cost, _)| {
+            left_cost
+                .partial_cmp(right_cost)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let match_cost = ords_with_cost.iter().map(|&(cost, _)| cost).sum();
+        let mut intersection = TwoPhaseIntersection {
+            approximation,
+            two_phase_ords: ords_with_cost.into_iter().map(|(_, ord)| ord).collect(),
+            num_scorers,
+            match_cost,
+        };
+        if intersection.approximation.doc() != TERMINATED && !intersection.matches() {
+            intersection.advance();
+        }
+        intersection
+    }
+}
+
+impl<TScorer: Scorer> DocSet for TwoPhaseIntersection<TScorer> {
+    fn advance(&mut self) -> DocId {
+        loop {
+            let doc = self.approximation.advance();
+            if doc == TERMINATED || self.matches() {
+                return doc;
+            }
+        }
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        let doc = self.approximation.seek(target);
+        if doc == TERMINATED || self.matches() {
+            return doc;
+        }
+        self.advance()
+    }
+
+    fn doc(&self) -> DocId {
+        self.approximation.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.approximation.size_hint()
+    }
+
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        if self.two_phase_ords.is_empty() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+}
+
+impl<TScorer: Scorer> TwoPhase for TwoPhaseIntersection<TScorer> {
+    fn approximation(&mut self) -> &mut dyn DocSet {
+        &mut self.approximation
+    }
+
+    fn matches(&mut self) -> bool {
+        let approximation = &mut self.approximation;
+        self.two_phase_ords
+            .iter()
+            .all(|&ord| approximation.docset_mut_specialized(ord).matches())
+    }
+
+    fn match_cost(&self) -> f32 {
+        self.match_cost
+    }
+}
+
+impl<TScorer: Scorer> Scorer for TwoPhaseIntersection<TScorer> {
+    fn score(&mut self) -> Score {
+        (0..self.num_scorers)
+            .map(|ord| {
+                self.approximation
+                    .docset_mut_specialized(ord)
+                    .docset_mut()
+                    .score()
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TwoPhaseIntersection;
+    use crate::docset::{DocSet, TERMINATED};
+    use crate::query::twophase_union::tests::CountingTwoPhaseScorer;
+    use crate::query::{ConstScorer, Scorer, VecDocSet};
+
+    #[test]
+    fn test_twophase_intersection_confirms_agreeing_docs() {
+        let (cheap_scorer, cheap_confirmations) =
+            CountingTwoPhaseScorer::new(vec![1, 3, 5, 7, 9], vec![3, 5, 7], 1f32);
+        let (costly_scorer, costly_confirmations) =
+            CountingTwoPhaseScorer::new((0..10).collect(), vec![3, 4, 7], 2f32);
+        let mut intersection = TwoPhaseIntersection::new(vec![
+            Box::new(costly_scorer) as Box<dyn Scorer>,
+            Box::new(ConstScorer::from(VecDocSet::from(vec![1, 3, 5, 7]))),
+            Box::new(cheap_scorer),
+        ]);
+        let mut scored_docs = Vec::new();
+        while intersection.doc() != TERMINATED {
+            scored_docs.push((intersection.doc(), intersection.score()));
+            intersection.advance();
+        }
+        assert_eq!(scored_docs, vec![(3, 3f32), (7, 3f32)]);
+        // The approximations agree on 1, 3, 5 and 7, and the cheap
+        // scorer rejects 1 before the costly scorer is confirmed.
+        assert_eq!(cheap_confirmations.get(), 4);
+        assert_eq!(costly_confirmations.get(), 3);
+    }
+
+    #[test]
+    fn test_twophase_intersection_approximation() {
+        let intersection = || {
+            TwoPhaseIntersection::new(vec![
+                CountingTwoPhaseScorer::new(vec![1, 3, 5, 7], vec![3, 7], 1f32).0,
+                CountingTwoPhaseScorer::new(vec![2, 3, 4, 5, 7], vec![3, 5, 7], 2f32).0,
+            ])
+        };
+        let mut seek_intersection = intersection();
+        assert_eq!(seek_intersection.doc(), 3);
+        assert_eq!(seek_intersection.seek(4), 7);
+        assert_eq!(seek_intersection.advance(), TERMINATED);
+
+        let mut intersection = intersection();
+        let two_phase = intersection.two_phase().unwrap();
+        assert_eq!(two_phase.match_cost(), 3f32);
+        let mut approximation_docs = Vec::new();
+        let mut doc = two_phase.approximation().doc();
+        while doc != TERMINATED {
+            approximation_docs.push((doc, two_phase.matches()));
+            doc = two_phase.approximation().advance();
+        }
+        assert_eq!(approximation_docs, vec![(3, true), (5, false), (7, true)]);
+    }
+}
@@ -0,0 +1,469 @@
+use crate::docset::{DocSet, TERMINATED};
+use crate::query::score_combiner::ScoreCombiner;
+use crate::query::{Scorer, TwoPhase, TwoPhaseApproximation};
+use crate::DocId;
+use crate::Score;
+
+/// Union of the approximations of the scorers of a `TwoPhaseUnion`.
+struct UnionApproximation<TScorer: Scorer> {
+    // Sorted by ascending match cost.
+    scorers: Vec<TwoPhaseApproximation<TScorer>>,
+    doc: DocId,
+}
+
+impl<TScorer: Scorer> UnionApproximation<TScorer> {
+    fn update_doc(&mut self) -> DocId {
+        self.doc = self
+            .scorers
+            .iter()
+            .map(DocSet::doc)
+            .min()
+            .unwrap_or(TERMINATED);
+        self.doc
+    }
+}
+
+impl<TScorer: Scorer> DocSet for UnionApproximation<TScorer> {
+    fn advance(&mut self) -> DocId {
+        let doc = self.doc;
+        if doc == TERMINATED {
+            return TERMINATED;
+        }
+        for scorer in &mut self.scorers {
+            if scorer.doc() == doc {
+                scorer.advance();
+            }
+        }
+        self.update_doc()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        for scorer in &mut self.scorers {
+            if scorer.doc() < target {
+                scorer.seek(target);
+            }
+        }
+        self.update_doc()
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.scorers
+            .iter()
+            .map(|scorer| scorer.size_hint())
+            .max()
+            .unwrap_or(0u32)
+    }
+}
+
+/// Union of `Scorer`s, some of which are two-phase scorers.
+///
+/// Unlike `Union`, the `TwoPhaseUnion` does not confirm the documents
+/// of its two-phase scorers eagerly. Its approximation is the union of
+/// the approximations of the scorers, and `.matches()` checks the scorers
+/// by ascending `match_cost()`, stopping at the first one that
+/// actually matches the document.
+/// A cheap scorer matching a document therefore spares the
+/// confirmation of the costly ones.
+///
+/// The `match_cost()` of the union is the lowest `match_cost()` of its
+/// scorers, as a single confirmation is enough.
+///
+/// When a document is scored, all of the scorers positioned on it
+/// are confirmed, and the scores of those which actually match are combined.
+/// Each scorer is confirmed at most once per document: the confirmations
+/// done by `.matches()` are reused by `.score()`.
+pub struct TwoPhaseUnion<TScorer: Scorer, TScoreCombiner> {
+    approximation: UnionApproximation<TScorer>,
+    match_cost: f32,
+    is_two_phase: bool,
+    // Document for which `confirmations` were computed.
+    confirmed_doc: DocId,
+    // Outcome of the confirmation of each scorer, if it was confirmed.
+    confirmations: Vec<Option<bool>>,
+    score_combiner: TScoreCombiner,
+}
+
+impl<TScorer, TScoreCombiner> From<Vec<TScorer>> for TwoPhaseUnion<TScorer, TScoreCombiner>
+where
+    TScoreCombiner: ScoreCombiner,
+    TScorer: Scorer,
+{
+    fn from(scorers: Vec<TScorer>) -> TwoPhaseUnion<TScorer, TScoreCombiner> {
+        TwoPhaseUnion::build(scorers, TScoreCombiner::default)
+    }
+}
+
+impl<TScorer: Scorer, TScoreCombiner: ScoreCombiner> TwoPhaseUnion<TScorer, TScoreCombiner> {
+    /// Creates a new `TwoPhaseUnion`, combining the scores with the
+    /// `ScoreCombiner` returned by `score_combiner_fn`.
+    pub fn build<TScoreCombinerFn: Fn() -> TScoreCombiner>(
+        scorers: Vec<TScorer>,
+        score_combiner_fn: TScoreCombinerFn,
+    ) -> TwoPhaseUnion<TScorer, TScoreCombiner> {
+        let mut scorers_with_cost: Vec<(f32, TwoPhaseApproximation<TScorer>)> = scorers
+            .into_iter()
+            .map(TwoPhaseApproximation::new)
+            .map(|mut scorer| (scorer.match_cost(), scorer))
+            .collect();
+        scorers_with_cost.sort_by(|(left_cost, _), (right_cost, _)| {
+            left_cost
+                .partial_cmp(right_cost)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let match_cost = scorers_with_cost
+            .first()
+            .map(|&(cost, _)| cost)
+            .unwrap_or(0f32);
+        let mut scorers: Vec<TwoPhaseApproximation<TScorer>> = scorers_with_cost
+            .into_iter()
+            .map(|(_, scorer)| scorer)
+            .collect();
+        let is_two_phase = scorers
+            .iter_mut()
+            .any(|scorer| scorer.docset_mut().two_phase().is_some());
+        let confirmations = vec![None; scorers.len()];
+        let mut union = TwoPhaseUnion {
+            approximation: UnionApproximation {
+                scorers,
+                doc: TERMINATED,
+            },
+            match_cost,
+            is_two_phase,
+            confirmed_doc: TERMINATED,
+            confirmations,
+            score_combiner: score_combiner_fn(),
+        };
+        if union.approximation.update_doc() != TERMINATED && !union.matches() {
+            union.advance();
+        }
+        union
+    }
+
+    /// Returns true iff the scorer of ordinal `ord` matches the current
+    /// document.
+    ///
+    /// The scorer is only confirmed once per document.
+    fn confirm(&mut self, ord: usize) -> bool {
+        let doc = self.approximation.doc();
+        if self.confirmed_doc != doc {
+            self.confirmed_doc = doc;
+            for confirmation in &mut self.confirmations {
+                *confirmation = None;
+            }
+        }
+        if let Some(confirmed) = self.confirmations[ord] {
+            return confirmed;
+        }
+        let scorer = &mut self.approximation.scorers[ord];
+        let confirmed = scorer.doc() == doc && scorer.matches();
+        self.confirmations[ord] = Some(confirmed);
+        confirmed
+    }
+}
+
+impl<TScorer, TScoreCombiner> DocSet for TwoPhaseUnion<TScorer, TScoreCombiner>
+where
+    TScorer: Scorer,
+    TScoreCombiner: ScoreCombiner,
+{
+    fn advance(&mut self) -> DocId {
+        loop {
+            let doc = self.approximation.advance();
+            if doc == TERMINATED || self.matches() {
+                return doc;
+            }
+        }
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        let doc = self.approximation.seek(target);
+        if doc == TERMINATED || self.matches() {
+            return doc;
+        }
+        self.advance()
+    }
+
+    fn doc(&self) -> DocId {
+        self.approximation.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.approximation.size_hint()
+    }
+
+    fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+        if self.is_two_phase {
+            Some(self)
+        } else {
+            None
+        }
+    }
+}
+
+impl<TScorer, TScoreCombiner> TwoPhase for TwoPhaseUnion<TScorer, TScoreCombiner>
+where
+    TScorer: Scorer,
+    TScoreCombiner: ScoreCombiner,
+{
+    fn approximation(&mut self) -> &mut dyn DocSet {
+        &mut self.approximation
+    }
+
+    fn matches(&mut self) -> bool {
+        (0..self.approximation.scorers.len()).any(|ord| self.confirm(ord))
+    }
+
+    fn match_cost(&self) -> f32 {
+        self.match_cost
+    }
+}
+
+impl<TScorer, TScoreCombiner> Scorer for TwoPhaseUnion<TScorer, TScoreCombiner>
+where
+    TScorer: Scorer,
+    TScoreCombiner: ScoreCombiner,
+{
+    fn score(&mut self) -> Score {
+        for ord in 0..self.approximation.scorers.len() {
+            if self.confirm(ord) {
+                let scorer = self.approximation.scorers[ord].docset_mut();
+                self.score_combiner.update(scorer);
+            }
+        }
+        let score = self.score_combiner.score();
+        self.score_combiner.clear();
+        score
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::TwoPhaseUnion;
+    use crate::collector::tests::TEST_COLLECTOR_WITH_SCORE;
+    use crate::docset::{DocSet, TERMINATED};
+    use crate::query::phrase_query::tests::create_index;
+    use crate::query::score_combiner::{SumCombiner, SumWithCoordsCombiner};
+    use crate::query::{
+        BooleanQuery, Occur, PhraseQuery, Query, Scorer, TermQuery, TwoPhase, VecDocSet,
+    };
+    use crate::schema::IndexRecordOption;
+    use crate::tests::assert_nearly_equals;
+    use crate::{DocAddress, DocId, Index, Score, Term};
+    use std::cell::Cell;
+    use std::collections::BTreeMap;
+    use std::rc::Rc;
+
+    /// Two-phase scorer counting its confirmations.
+    pub(crate) struct CountingTwoPhaseScorer {
+        approximation: VecDocSet,
+        matching_docs: Vec<DocId>,
+        match_cost: f32,
+        num_confirmations: Rc<Cell<usize>>,
+    }
+
+    impl CountingTwoPhaseScorer {
+        pub(crate) fn new(
+            approximation_docs: Vec<DocId>,
+            matching_docs: Vec<DocId>,
+            match_cost: f32,
+        ) -> (CountingTwoPhaseScorer, Rc<Cell<usize>>) {
+            let num_confirmations = Rc::new(Cell::new(0));
+            let scorer = CountingTwoPhaseScorer {
+                approximation: VecDocSet::from(approximation_docs),
+                matching_docs,
+                match_cost,
+                num_confirmations: num_confirmations.clone(),
+            };
+            (scorer, num_confirmations)
+        }
+    }
+
+    impl DocSet for CountingTwoPhaseScorer {
+        fn advance(&mut self) -> DocId {
+            loop {
+                let doc = self.approximation.advance();
+                if doc == TERMINATED || self.matches() {
+                    return doc;
+                }
+            }
+        }
+
+        fn doc(&self) -> DocId {
+            self.approximation.doc()
+        }
+
+        fn size_hint(&self) -> u32 {
+            self.approximation.size_hint()
+        }
+
+        fn two_phase(&mut self) -> Option<&mut dyn TwoPhase> {
+            Some(self)
+        }
+    }
+
+    impl TwoPhase for CountingTwoPhaseScorer {
+        fn approximation(&mut self) -> &mut dyn DocSet {
+            &mut self.approximation
+        }
+
+        fn matches(&mut self) -> bool {
+            self.num_confirmations.set(self.num_confirmations.get() + 1);
+            self.matching_docs.contains(&self.approximation.doc())
+        }
+
+        fn match_cost(&self) -> f32 {
+            self.match_cost
+        }
+    }
+
+    impl Scorer for CountingTwoPhaseScorer {
+        fn score(&mut self) -> Score {
+            1f32
+        }
+    }
+
+    fn term_query(index: &Index, text: &str) -> Box<dyn Query> {
+        let text_field = index.schema().get_field("text").unwrap();
+        Box::new(TermQuery::new(
+            Term::from_field_text(text_field, text),
+            IndexRecordOption::WithFreqs,
+        ))
+    }
+
+    fn phrase_query(index: &Index, texts: &[&str]) -> Box<dyn Query> {
+        let text_field = index.schema().get_field("text").unwrap();
+        Box::new(PhraseQuery::new(
+            texts
+                .iter()
+                .map(|text| Term::from_field_text(text_field, text))
+                .collect(),
+        ))
+    }
+
+    fn search(index: &Index, query: &dyn Query) -> BTreeMap<DocId, f32> {
+        let searcher = index.reader().unwrap().searcher();
+        let test_fruits = searcher.search(query, &TEST_COLLECTOR_WITH_SCORE).unwrap();
+        test_fruits
+            .docs()
+            .iter()
+            .map(|doc| doc.doc())
+            .zip(test_fruits.scores().iter().cloned())
+            .collect()
+    }
+
+    #[test]
+    fn test_twophase_union_against_brute_force() {
+        let index = create_index(&[
+            "a b", "a c b", "c", "b a", "a b c", "d", "a x b c", "d a b", "b d",
+        ]);
+        let clauses = vec![term_query(&index, "c"), phrase_query(&index, &["a", "b"])];
+        let mut expected: BTreeMap<DocId, f32> = BTreeMap::new();
+        for clause in &clauses {
+            for (doc, score) in search(&index, clause.as_ref()) {
+                *expected.entry(doc).or_insert(0f32) += score;
+            }
+        }
+        let union_query = BooleanQuery::from(
+            clauses
+                .iter()
+                .map(|clause| (Occur::Should, clause.box_clone()))
+                .collect::<Vec<_>>(),
+        );
+        let union_docs = search(&index, &union_query);
+        assert_eq!(
+            union_docs.keys().cloned().collect::<Vec<DocId>>(),
+            vec![0, 1, 2, 4, 6, 7]
+        );
+        assert_eq!(
+            union_docs.keys().collect::<Vec<_>>(),
+            expected.keys().collect::<Vec<_>>()
+        );
+        for (doc, score) in &union_docs {
+            assert_nearly_equals(expected[doc], *score);
+        }
+        let searcher = index.reader().unwrap().searcher();
+        assert_eq!(union_query.count(&searcher).unwrap(), expected.len());
+
+        // Within a conjunction, or as an exclusion.
+        let must_query = BooleanQuery::from(vec![
+            (Occur::Must, term_query(&index, "d")),
+            (Occur::Must, Box::new(union_query.clone()) as Box<dyn Query>),
+        ]);
+        assert_eq!(
+            search(&index, &must_query).keys().collect::<Vec<_>>(),
+            vec![&7]
+        );
+        let must_not_query = BooleanQuery::from(vec![
+            (Occur::Should, term_query(&index, "b")),
+            (Occur::MustNot, Box::new(union_query) as Box<dyn Query>),
+        ]);
+        assert_eq!(
+            search(&index, &must_not_query).keys().collect::<Vec<_>>(),
+            vec![&3, &8]
+        );
+    }
+
+    #[test]
+    fn test_twophase_union_confirms_once_per_doc() {
+        let (cheap_scorer, cheap_confirmations) =
+            CountingTwoPhaseScorer::new(vec![1, 2, 3, 5], vec![2, 5], 1f32);
+        let (costly_scorer, costly_confirmations) =
+            CountingTwoPhaseScorer::new(vec![2, 3, 4, 5], vec![3, 5], 2f32);
+        let mut union = TwoPhaseUnion::<_, SumCombiner>::from(vec![costly_scorer, cheap_scorer]);
+        let mut scored_docs = Vec::new();
+        while union.doc() != TERMINATED {
+            scored_docs.push((union.doc(), union.score()));
+            union.advance();
+        }
+        assert_eq!(scored_docs, vec![(2, 1f32), (3, 1f32), (5, 2f32)]);
+        // Scoring reuses the confirmations done to find the matching documents:
+        // each scorer is confirmed once for each document of its approximation.
+        assert_eq!(cheap_confirmations.get(), 4);
+        assert_eq!(costly_confirmations.get(), 4);
+    }
+
+    #[test]
+    fn test_twophase_union_approximation() {
+        let index = create_index(&["a b", "a c b", "c", "b a", "a b c", "d"]);
+        let searcher = index.reader().unwrap().searcher();
+        let union_query = BooleanQuery::from(vec![
+            (Occur::Should, term_query(&index, "c")),
+            (Occur::Should, phrase_query(&index, &["a", "b"])),
+        ]);
+        let weight = union_query.weight(&searcher, true).unwrap();
+        let mut scorer = weight.scorer(searcher.segment_reader(0), 1.0f32).unwrap();
+        assert!(scorer.is::<TwoPhaseUnion<Box<dyn Scorer>, SumWithCoordsCombiner>>());
+        let two_phase = scorer.two_phase().unwrap();
+        // The term scorer has no match cost.
+        assert_eq!(two_phase.match_cost(), 0f32);
+        let mut approximation_docs = Vec::new();
+        let mut doc = two_phase.approximation().doc();
+        while doc != TERMINATED {
+            approximation_docs.push((doc, two_phase.matches()));
+            doc = two_phase.approximation().advance();
+        }
+        assert_eq!(
+            approximation_docs,
+            vec![(0, true), (1, true), (2, true), (3, false), (4, true)]
+        );
+
+        let phrases_query = BooleanQuery::from(vec![
+            (Occur::Should, phrase_query(&index, &["a", "b"])),
+            (Occur::Should, phrase_query(&index, &["a", "b", "c"])),
+        ]);
+        let weight = phrases_query.weight(&searcher, true).unwrap();
+        let mut scorer = weight.scorer(searcher.segment_reader(0), 1.0f32).unwrap();
+        assert_eq!(scorer.two_phase().unwrap().match_cost(), 2f32);
+        assert_eq!(scorer.doc(), 0);
+        assert_eq!(scorer.advance(), 4);
+        let explanation = phrases_query
+            .explain(&searcher, DocAddress(0u32, 4u32))
+            .unwrap();
+        assert_nearly_equals(explanation.value(), scorer.score());
+        assert_eq!(scorer.advance(), TERMINATED);
+    }
+}
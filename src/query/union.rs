@@ -44,6 +44,23 @@ where
     TScorer: Scorer,
 {
     fn from(docsets: Vec<TScorer>) -> Union<TScorer, TScoreCombiner> {
+        Union::build(docsets, TScoreCombiner::default)
+    }
+}
+
+impl<TScorer, TScoreCombiner> Union<TScorer, TScoreCombiner>
+where
+    TScoreCombiner: ScoreCombiner,
+    TScorer: Scorer,
+{
+    /// Creates a `Union` whose score combiners are built by
+    /// `score_combiner_fn`.
+    ///
+    /// This is useful for score combiners holding some parameters.
+    pub fn build<TScoreCombinerFn: Fn() -> TScoreCombiner>(
+        docsets: Vec<TScorer>,
+        score_combiner_fn: TScoreCombinerFn,
+    ) -> Union<TScorer, TScoreCombiner> {
         let non_empty_docsets: Vec<TScorer> = docsets
             .into_iter()
             .filter(|docset| docset.doc() != TERMINATED)
@@ -51,7 +68,7 @@ where
         let mut union = Union {
             docsets: non_empty_docsets,
             bitsets: Box::new([TinySet::empty(); HORIZON_NUM_TINYBITSETS]),
-            scores: Box::new([TScoreCombiner::default(); HORIZON as usize]),
+            scores: Box::new([score_combiner_fn(); HORIZON as usize]),
             cursor: HORIZON_NUM_TINYBITSETS,
             offset: 0,
             doc: 0,
@@ -241,6 +258,31 @@ where
     fn score(&mut self) -> Score {
         self.score
     }
+
+    fn for_each(&mut self, callback: &mut dyn FnMut(DocId, Score)) {
+        if self.doc == TERMINATED {
+            return;
+        }
+        callback(self.doc, self.score);
+        loop {
+            // Drains the buffered documents without going through `.advance()`.
+            while self.cursor < HORIZON_NUM_TINYBITSETS {
+                let bitset = &mut self.bitsets[self.cursor];
+                while let Some(val) = bitset.pop_lowest() {
+                    let delta = val + (self.cursor as u32) * 64;
+                    let score_combiner = &mut self.scores[delta as usize];
+                    let score = score_combiner.score();
+                    score_combiner.clear();
+                    callback(self.offset + delta, score);
+                }
+                self.cursor += 1;
+            }
+            if !self.refill() {
+                break;
+            }
+        }
+        self.doc = TERMINATED;
+    }
 }
 
 #[cfg(test)]
@@ -250,11 +292,12 @@ mod tests {
     use super::HORIZON;
     use crate::docset::{DocSet, TERMINATED};
     use crate::postings::tests::test_skip_against_unoptimized;
-    use crate::query::score_combiner::DoNothingCombiner;
+    use crate::query::score_combiner::{DoNothingCombiner, SumCombiner};
     use crate::query::ConstScorer;
-    use crate::query::VecDocSet;
+    use crate::query::{Scorer, VecDocSet};
     use crate::tests;
     use crate::DocId;
+    use crate::Score;
     use std::collections::BTreeSet;
 
     fn aux_test_union(vals: Vec<Vec<u32>>) {
@@ -377,6 +420,45 @@ mod tests {
         );
     }
 
+    fn aux_test_union_for_each(vals: &[Vec<DocId>], seek_target: DocId) {
+        let make_union = || {
+            let mut union = Union::<_, SumCombiner>::from(
+                vals.iter()
+                    .cloned()
+                    .enumerate()
+                    .map(|(ord, docs)| ConstScorer::new(VecDocSet::from(docs), (ord + 1) as Score))
+                    .collect::<Vec<ConstScorer<VecDocSet>>>(),
+            );
+            union.seek(seek_target);
+            union
+        };
+        let mut expected: Vec<(DocId, Score)> = Vec::new();
+        let mut union = make_union();
+        while union.doc() != TERMINATED {
+            expected.push((union.doc(), union.score()));
+            union.advance();
+        }
+        let mut union = make_union();
+        let mut output: Vec<(DocId, Score)> = Vec::new();
+        union.for_each(&mut |doc, score| output.push((doc, score)));
+        assert_eq!(output, expected);
+        assert_eq!(union.doc(), TERMINATED);
+        assert_eq!(union.advance(), TERMINATED);
+    }
+
+    #[test]
+    fn test_union_for_each() {
+        aux_test_union_for_each(&[vec![1, 3, 5], vec![1, 2, 3, 4]], 0);
+        aux_test_union_for_each(&[vec![], vec![]], 0);
+        aux_test_union_for_each(&[vec![1, 3333, 100_000], vec![2, 3333, 100_001]], 3000);
+        aux_test_union_for_each(&[vec![1, 2, 3], vec![4]], 5);
+        let vals: Vec<Vec<DocId>> = (0..3)
+            .map(|seed| tests::sample_with_seed(10_000, 0.1, seed))
+            .collect();
+        aux_test_union_for_each(&vals, 0);
+        aux_test_union_for_each(&vals, 5_000);
+    }
+
     #[test]
     fn test_union_skip_specific() {
         test_aux_union_skip(
@@ -393,11 +475,12 @@ mod tests {
 #[cfg(all(test, feature = "unstable"))]
 mod bench {
 
-    use crate::query::score_combiner::DoNothingCombiner;
-    use crate::query::{ConstScorer, Union, VecDocSet};
+    use crate::query::score_combiner::{DoNothingCombiner, SumCombiner};
+    use crate::query::{ConstScorer, Scorer, Union, VecDocSet};
     use crate::tests;
     use crate::DocId;
     use crate::DocSet;
+    use crate::TERMINATED;
     use test::Bencher;
 
     #[bench]
@@ -411,12 +494,58 @@ mod bench {
                 union_docset
                     .iter()
                     .map(|doc_ids| VecDocSet::from(doc_ids.clone()))
-                    .map(ConstScorer::new)
+                    .map(ConstScorer::from)
+                    .collect::<Vec<_>>(),
+            );
+            while v.advance() != TERMINATED {}
+        });
+    }
+
+    #[bench]
+    fn bench_union_3_high_for_each(bench: &mut Bencher) {
+        let union_docset: Vec<Vec<DocId>> = vec![
+            tests::sample_with_seed(100_000, 0.1, 0),
+            tests::sample_with_seed(100_000, 0.2, 1),
+            tests::sample_with_seed(100_000, 0.3, 2),
+        ];
+        bench.iter(|| {
+            let mut v = Union::<_, SumCombiner>::from(
+                union_docset
+                    .iter()
+                    .map(|doc_ids| VecDocSet::from(doc_ids.clone()))
+                    .map(ConstScorer::from)
                     .collect::<Vec<_>>(),
             );
-            while v.advance() {}
+            let mut total_score = 0f32;
+            v.for_each(&mut |_doc, score| total_score += score);
+            total_score
         });
     }
+
+    #[bench]
+    fn bench_union_3_high_manual_loop(bench: &mut Bencher) {
+        let union_docset: Vec<Vec<DocId>> = vec![
+            tests::sample_with_seed(100_000, 0.1, 0),
+            tests::sample_with_seed(100_000, 0.2, 1),
+            tests::sample_with_seed(100_000, 0.3, 2),
+        ];
+        bench.iter(|| {
+            let mut v = Union::<_, SumCombiner>::from(
+                union_docset
+                    .iter()
+                    .map(|doc_ids| VecDocSet::from(doc_ids.clone()))
+                    .map(ConstScorer::from)
+                    .collect::<Vec<_>>(),
+            );
+            let mut total_score = 0f32;
+            while v.doc() != TERMINATED {
+                total_score += v.score();
+                v.advance();
+            }
+            total_score
+        });
+    }
+
     #[bench]
     fn bench_union_3_low(bench: &mut Bencher) {
         let union_docset: Vec<Vec<DocId>> = vec![
@@ -429,10 +558,10 @@ mod bench {
                 union_docset
                     .iter()
                     .map(|doc_ids| VecDocSet::from(doc_ids.clone()))
-                    .map(ConstScorer::new)
+                    .map(ConstScorer::from)
                     .collect::<Vec<_>>(),
             );
-            while v.advance() {}
+            while v.advance() != TERMINATED {}
         });
     }
 }
@@ -1,5 +1,6 @@
 use super::Scorer;
 use crate::core::SegmentReader;
+use crate::fastfield::DeleteBitSet;
 use crate::query::Explanation;
 use crate::{DocId, Score, TERMINATED};
 
@@ -9,11 +10,7 @@ pub(crate) fn for_each_scorer<TScorer: Scorer + ?Sized>(
     scorer: &mut TScorer,
     callback: &mut dyn FnMut(DocId, Score),
 ) {
-    let mut doc = scorer.doc();
-    while doc != TERMINATED {
-        callback(doc, scorer.score());
-        doc = scorer.advance();
-    }
+    scorer.for_each(callback);
 }
 
 /// Calls `callback` with all of the `(doc, score)` for which score
@@ -41,6 +38,35 @@ pub(crate) fn for_each_pruning_scorer<TScorer: Scorer + ?Sized>(
     }
 }
 
+const COLLECT_BUFFER_LEN: usize = 128;
+
+/// Collects the sorted list of the non-deleted documents matched
+/// by the scorer, without computing any score.
+fn collect_docs<TScorer: Scorer + ?Sized>(
+    scorer: &mut TScorer,
+    delete_bitset_opt: Option<&DeleteBitSet>,
+) -> Vec<DocId> {
+    let mut docs = Vec::new();
+    let mut buffer = [0u32; COLLECT_BUFFER_LEN];
+    loop {
+        let num_docs = scorer.fill_buffer(&mut buffer[..]);
+        let buffered_docs = &buffer[..num_docs];
+        if let Some(delete_bitset) = delete_bitset_opt {
+            docs.extend(
+                buffered_docs
+                    .iter()
+                    .cloned()
+                    .filter(|&doc| !delete_bitset.is_deleted(doc)),
+            );
+        } else {
+            docs.extend_from_slice(buffered_docs);
+        }
+        if num_docs < COLLECT_BUFFER_LEN {
+            return docs;
+        }
+    }
+}
+
 /// A Weight is the specialization of a Query
 /// for a given set of segments.
 ///
@@ -78,6 +104,29 @@ pub trait Weight: Send + Sync + 'static {
         Ok(())
     }
 
+    /// Scores the documents matched in the given segment
+    /// with `scorer_fn`, in two passes.
+    ///
+    /// The first pass collects the sorted list of matching documents, without
+    /// computing the scores of the scorer. Deleted documents are skipped.
+    /// The second pass calls `scorer_fn` for each of these documents,
+    /// in increasing doc id order.
+    ///
+    /// This is useful when `scorer_fn` is expensive, typically when
+    /// it relies on several fast fields: the fast fields are then read
+    /// sequentially, in a cache friendly manner.
+    ///
+    /// Returns the `(doc, score)` pairs, sorted by doc id.
+    fn collect_then_score(
+        &self,
+        reader: &SegmentReader,
+        scorer_fn: &mut dyn FnMut(DocId) -> Score,
+    ) -> crate::Result<Vec<(DocId, Score)>> {
+        let mut scorer = self.scorer(reader, 1.0f32)?;
+        let docs = collect_docs(scorer.as_mut(), reader.delete_bitset());
+        Ok(docs.into_iter().map(|doc| (doc, scorer_fn(doc))).collect())
+    }
+
     /// Calls `callback` with all of the `(doc, score)` for which score
     /// is exceeding a given threshold.
     ///
@@ -99,3 +148,57 @@ pub trait Weight: Send + Sync + 'static {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::query::{Query, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, FAST, INDEXED, TEXT};
+    use crate::{DocId, Index, Score, Term};
+
+    #[test]
+    fn test_collect_then_score() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let popularity_field = schema_builder.add_u64_field("popularity", FAST | INDEXED);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_with_num_threads(1, 3_000_000).unwrap();
+            for i in 0u64..1_000u64 {
+                let text = if i % 3 == 0 { "a b" } else { "b" };
+                index_writer.add_document(doc!(text_field=>text, popularity_field=>i * 7 % 13));
+            }
+            index_writer.delete_term(Term::from_field_u64(popularity_field, 5u64));
+            assert!(index_writer.commit().is_ok());
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let popularity_reader = segment_reader.fast_fields().u64(popularity_field).unwrap();
+        let query = TermQuery::new(
+            Term::from_field_text(text_field, "a"),
+            IndexRecordOption::Basic,
+        );
+        let weight = query.weight(&searcher, false).unwrap();
+
+        let mut single_pass: Vec<(DocId, Score)> = Vec::new();
+        weight
+            .for_each(segment_reader, &mut |doc, _score| {
+                if !segment_reader.is_deleted(doc) {
+                    single_pass.push((doc, popularity_reader.get(doc) as Score));
+                }
+            })
+            .unwrap();
+
+        let mut read_docs: Vec<DocId> = Vec::new();
+        let two_passes = weight
+            .collect_then_score(segment_reader, &mut |doc| {
+                read_docs.push(doc);
+                popularity_reader.get(doc) as Score
+            })
+            .unwrap();
+
+        assert_eq!(two_passes.len(), 309);
+        assert_eq!(two_passes, single_pass);
+        assert!(read_docs.windows(2).all(|docs| docs[0] < docs[1]));
+    }
+}